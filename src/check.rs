@@ -0,0 +1,282 @@
+use super::{error::Location, objects::*};
+use std::{collections::HashSet, fmt};
+
+/// A problem found before running a program, which doesn't stop it from
+/// running.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: warning: {}", self.location, self.message)
+    }
+}
+
+/// The variables that have a value on every path leading to a statement, or
+/// `None` when the statement can never be reached (after `stop` or `breek`).
+type Assigned = Option<HashSet<String>>;
+
+struct Checker<'a> {
+    ctx: &'a Context,
+    reads: HashSet<String>,
+    writes: HashSet<String>,
+    unassigned: Vec<(usize, usize, String)>,
+    calls: Vec<String>,
+}
+
+impl Context {
+    /// Looks for variables that are read before they are assigned and for
+    /// variables and hardware that are declared but never used, without
+    /// executing anything. Has to be called after `parse`.
+    pub fn check(&self) -> Vec<Warning> {
+        let mut checker = Checker {
+            ctx: self,
+            reads: HashSet::new(),
+            writes: HashSet::new(),
+            unassigned: Vec::new(),
+            calls: Vec::new(),
+        };
+        checker.block(&self.code, Some(HashSet::new()));
+        // procedures that are never called still use their variables
+        for procedure in self.procedures.values() {
+            checker.block(&procedure.block, None);
+        }
+        checker.warnings()
+    }
+}
+
+impl Checker<'_> {
+    fn block(&mut self, block: &CodeBlock, mut assigned: Assigned) -> Assigned {
+        for obj in &block.objects {
+            let (line, column) = obj.position();
+
+            match obj {
+                LangObject::Assignment(v) => {
+                    self.expression(&v.expression, assigned.as_ref(), line, column);
+                    self.writes.insert(v.var.clone());
+                    if let Some(a) = &mut assigned {
+                        a.insert(v.var.clone());
+                    }
+                },
+                LangObject::Print(v) => {
+                    self.expression(&v.expression, assigned.as_ref(), line, column)
+                },
+                LangObject::Zolang(v) => {
+                    self.condition(&v.expression, assigned.as_ref(), line, column);
+                    self.block(&v.block, assigned.clone());
+                },
+                LangObject::Herhaal(v) => {
+                    self.expression(&v.count, assigned.as_ref(), line, column);
+                    self.block(&v.block, assigned.clone());
+                },
+                LangObject::Als(v) => {
+                    self.condition(&v.expression, assigned.as_ref(), line, column);
+                    let if_assigned = self.block(&v.if_block, assigned.clone());
+                    let else_assigned = match &v.else_block {
+                        Some(block) => self.block(block, assigned),
+                        None => assigned,
+                    };
+                    assigned = merge(if_assigned, else_assigned);
+                },
+                LangObject::Call(v) => {
+                    if let Some(procedure) = self.ctx.procedures.get(&v.name) {
+                        if !self.calls.contains(&v.name) {
+                            self.calls.push(v.name.clone());
+                            assigned = self.block(&procedure.block, assigned);
+                            self.calls.pop();
+                        }
+                    }
+                },
+                LangObject::Break {
+                    ..
+                }
+                | LangObject::Stop {
+                    ..
+                } => assigned = None,
+                LangObject::StepForwards {
+                    ..
+                }
+                | LangObject::StepBackwards {
+                    ..
+                }
+                | LangObject::TurnLeft {
+                    ..
+                }
+                | LangObject::TurnRight {
+                    ..
+                } => {},
+            }
+        }
+
+        assigned
+    }
+
+    fn condition(
+        &mut self,
+        condition: &Condition,
+        assigned: Option<&HashSet<String>>,
+        line: usize,
+        column: usize,
+    ) {
+        match condition {
+            Condition::Compare(c) => {
+                self.expression(&c.left, assigned, line, column);
+                self.expression(&c.right, assigned, line, column);
+            },
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                self.condition(a, assigned, line, column);
+                self.condition(b, assigned, line, column);
+            },
+            Condition::Not(a) => self.condition(a, assigned, line, column),
+        }
+    }
+
+    fn expression(
+        &mut self,
+        expression: &ExpressionVar,
+        assigned: Option<&HashSet<String>>,
+        line: usize,
+        column: usize,
+    ) {
+        let name = match expression {
+            ExpressionVar::Variable(name) => {
+                if assigned.is_some_and(|a| !a.contains(name)) {
+                    self.unassigned.push((line, column, name.clone()));
+                }
+                name.as_str()
+            },
+            ExpressionVar::Expression(e) => {
+                self.expression(&e.left, assigned, line, column);
+                self.expression(&e.right, assigned, line, column);
+                return;
+            },
+            ExpressionVar::Int(_) => return,
+            ExpressionVar::Kompas => Hardware::Kompas.name(),
+            ExpressionVar::ZwOog => Hardware::ZwOog.name(),
+            ExpressionVar::KleurOog => Hardware::KleurOog.name(),
+        };
+        self.reads.insert(name.to_owned());
+    }
+
+    fn warnings(self) -> Vec<Warning> {
+        let mut found = Vec::new();
+
+        for (line, column, name) in &self.unassigned {
+            let message = if self.writes.contains(name) {
+                format!("variable {name} might be read before it is assigned a value")
+            } else {
+                format!("variable {name} is read but never assigned a value")
+            };
+            found.push((*line, *column, message));
+        }
+
+        for (name, symbol) in &self.ctx.symbols {
+            if self.reads.contains(name) {
+                continue;
+            }
+            let rules = &self.ctx.rules;
+            let message = match &symbol.kind {
+                SymbolKind::Variable if self.writes.contains(name) => format!(
+                    "variable {name} is assigned but never read, wasting {} points",
+                    rules.var_hardware
+                ),
+                SymbolKind::Variable => format!(
+                    "variable {name} is never used, wasting {} points",
+                    rules.var_hardware
+                ),
+                SymbolKind::Hardware(h) => {
+                    format!("{name} is never used, wasting {} points", h.cost(rules))
+                },
+                SymbolKind::Procedure => continue,
+            };
+            found.push((symbol.line, symbol.column, message));
+        }
+
+        found.sort();
+        found.dedup();
+        found
+            .into_iter()
+            .map(|(line, column, message)| Warning {
+                location: self.ctx.at(line, column),
+                message,
+            })
+            .collect()
+    }
+}
+
+fn merge(a: Assigned, b: Assigned) -> Assigned {
+    match (a, b) {
+        (None, other) | (other, None) => other,
+        (Some(a), Some(b)) => Some(a.intersection(&b).cloned().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Glade;
+
+    fn check(code: &str, dialect: Dialect) -> Vec<String> {
+        let glade = Glade::parse_str("glade.csv", "s1;w;w;t1").expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        ctx.dialect = dialect;
+        ctx.parse().expect("the code is valid");
+        ctx.check().iter().map(Warning::to_string).collect()
+    }
+
+    #[test]
+    fn branches() {
+        let code = "gebruik a\nals 1 == 1 {\n    a = 1\n}\nprint a";
+        assert_eq!(
+            check(code, Dialect::Standard),
+            ["instructions.txt:5:1: warning: variable a might be read before it is assigned a value"]
+        );
+
+        let code = "gebruik a\nals 1 == 1 {\n    a = 1\n} anders {\n    a = 2\n}\nprint a";
+        assert!(check(code, Dialect::Standard).is_empty());
+    }
+
+    #[test]
+    fn unreachable() {
+        let code = "gebruik a\nals 1 == 1 {\n    a = 1\n} anders {\n    stop\n}\nprint a";
+        assert!(check(code, Dialect::Standard).is_empty());
+
+        let code = "gebruik a\nzolang 1 == 1 {\n    breek\n    print a\n}\nprint a";
+        assert_eq!(
+            check(code, Dialect::Standard),
+            ["instructions.txt:6:1: warning: variable a is read but never assigned a value"]
+        );
+    }
+
+    #[test]
+    fn procedures() {
+        let code = "gebruik a\nfunctie zet {\n    a = 1\n}\nroep zet\nprint a";
+        assert!(check(code, Dialect::Extended).is_empty());
+
+        let code = "gebruik a\nfunctie zet {\n    a = 1\n}\nprint a";
+        assert_eq!(
+            check(code, Dialect::Extended),
+            ["instructions.txt:5:1: warning: variable a might be read before it is assigned a value"]
+        );
+
+        // a procedure that is never called still reads its variables
+        let code = "gebruik a\nfunctie toon {\n    print a\n}";
+        assert!(check(code, Dialect::Extended).is_empty());
+    }
+
+    #[test]
+    fn unused() {
+        let code = "gebruik a\ngebruik b\ngebruik kleurOog\nb = 1";
+        assert_eq!(
+            check(code, Dialect::Standard),
+            [
+                "instructions.txt:1:9: warning: variable a is never used, wasting 1 points",
+                "instructions.txt:2:9: warning: variable b is assigned but never read, wasting 1 points",
+                "instructions.txt:3:9: warning: kleurOog is never used, wasting 15 points",
+            ]
+        );
+    }
+}
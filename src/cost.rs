@@ -0,0 +1,288 @@
+use super::{objects::*, rules::Rules};
+use std::fmt;
+
+/// The usage points of a single iteration of a `zolang` or `herhaal` loop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopCost {
+    pub kind: &'static str,
+    pub line: usize,
+    pub column: usize,
+    pub per_iteration: i32,
+}
+
+/// The usage points of the condition and of each branch of an `als`, the
+/// last branch is the `anders` block (0 when there is none).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BranchCost {
+    pub line: usize,
+    pub column: usize,
+    pub condition: i32,
+    pub branches: Vec<i32>,
+}
+
+/// A cost estimate made without running the program.
+///
+/// The total costs of a run are `fixed + once` plus `per_iteration` for every
+/// iteration of every loop. Loops nested in other loops are counted
+/// separately, so their iterations aren't part of the iteration costs of the
+/// loop around them. When an `als` is passed the most expensive branch is
+/// counted, and `en`/`of` conditions are counted as if both sides are
+/// evaluated. Bonuses and collisions are left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostEstimate {
+    /// The hardware and software points, charged before the program starts.
+    pub fixed: i32,
+    /// The usage points of the code that runs once.
+    pub once: i32,
+    pub loops: Vec<LoopCost>,
+    pub branches: Vec<BranchCost>,
+    /// Set when a procedure calls itself, its costs are then only counted
+    /// for the outermost call.
+    pub recursive: bool,
+}
+
+struct Estimator<'a> {
+    ctx: &'a Context,
+    estimate: CostEstimate,
+    calls: Vec<String>,
+}
+
+impl Context {
+    /// Estimates the costs of the program from the parsed code, has to be
+    /// called after `parse`.
+    pub fn estimate(&self) -> CostEstimate {
+        let mut estimator = Estimator {
+            ctx: self,
+            estimate: CostEstimate {
+                fixed: self.points,
+                once: 0,
+                loops: Vec::new(),
+                branches: Vec::new(),
+                recursive: false,
+            },
+            calls: Vec::new(),
+        };
+        estimator.estimate.once = estimator.block(&self.code);
+
+        let mut estimate = estimator.estimate;
+        estimate.loops.sort_by_key(|l| (l.line, l.column));
+        estimate.branches.sort_by_key(|b| (b.line, b.column));
+        estimate
+    }
+}
+
+impl Estimator<'_> {
+    /// Returns the usage points of running `block` once, without the
+    /// iterations of the loops in it.
+    fn block(&mut self, block: &CodeBlock) -> i32 {
+        block.objects.iter().map(|obj| self.object(obj)).sum()
+    }
+
+    fn object(&mut self, obj: &LangObject) -> i32 {
+        let (line, column) = obj.position();
+        let rules = &self.ctx.rules;
+
+        match obj {
+            LangObject::Zolang(v) => {
+                let condition = condition(rules, &v.expression);
+                let per_iteration = condition + self.block(&v.block);
+                self.add_loop("zolang", line, column, per_iteration);
+                // the condition is checked once more when the loop ends
+                condition
+            },
+            LangObject::Herhaal(v) => {
+                let per_iteration = rules.herhaal_usage + self.block(&v.block);
+                self.add_loop("herhaal", line, column, per_iteration);
+                expression(rules, &v.count)
+            },
+            LangObject::Als(v) => {
+                let condition = condition(rules, &v.expression);
+                let mut branches = vec![self.block(&v.if_block)];
+                branches.push(v.else_block.as_ref().map_or(0, |b| self.block(b)));
+                let most = branches.iter().copied().max().unwrap_or(0);
+                if !self
+                    .estimate
+                    .branches
+                    .iter()
+                    .any(|b| (b.line, b.column) == (line, column))
+                {
+                    self.estimate.branches.push(BranchCost {
+                        line,
+                        column,
+                        condition,
+                        branches,
+                    });
+                }
+                condition + most
+            },
+            LangObject::Assignment(v) => rules.assignment_usage + expression(rules, &v.expression),
+            LangObject::Print(v) if self.ctx.charge_print => expression(rules, &v.expression),
+            LangObject::Call(v) => {
+                let Some(procedure) = self.ctx.procedures.get(&v.name) else {
+                    return 0;
+                };
+                if self.calls.contains(&v.name) {
+                    self.estimate.recursive = true;
+                    return 0;
+                }
+                self.calls.push(v.name.clone());
+                let cost = self.block(&procedure.block);
+                self.calls.pop();
+                cost
+            },
+            LangObject::TurnLeft {
+                ..
+            } => rules.turnleft_usage,
+            LangObject::TurnRight {
+                ..
+            } => rules.turnright_usage,
+            LangObject::StepForwards {
+                ..
+            } => rules.stepforward_usage,
+            LangObject::StepBackwards {
+                ..
+            } => rules.stepbackwards_usage,
+            // prints are free by default
+            LangObject::Print(_)
+            | LangObject::Break {
+                ..
+            }
+            | LangObject::Stop {
+                ..
+            } => 0,
+        }
+    }
+
+    fn add_loop(&mut self, kind: &'static str, line: usize, column: usize, per_iteration: i32) {
+        // a loop in a procedure is reached once for every call
+        if self
+            .estimate
+            .loops
+            .iter()
+            .any(|l| (l.line, l.column) == (line, column))
+        {
+            return;
+        }
+        self.estimate.loops.push(LoopCost {
+            kind,
+            line,
+            column,
+            per_iteration,
+        });
+    }
+}
+
+fn condition(rules: &Rules, condition: &Condition) -> i32 {
+    match condition {
+        Condition::Compare(c) => {
+            rules.comparison_usage + expression(rules, &c.left) + expression(rules, &c.right)
+        },
+        Condition::And(a, b) | Condition::Or(a, b) => {
+            self::condition(rules, a) + self::condition(rules, b)
+        },
+        Condition::Not(a) => self::condition(rules, a),
+    }
+}
+
+fn expression(rules: &Rules, expression: &ExpressionVar) -> i32 {
+    match expression {
+        ExpressionVar::Variable(_) | ExpressionVar::Int(_) => 0,
+        ExpressionVar::Kompas => rules.kompas_usage,
+        ExpressionVar::ZwOog => rules.zwoog_usage,
+        ExpressionVar::KleurOog => rules.kleuroog_usage,
+        ExpressionVar::Expression(e) => {
+            rules.operation_usage
+                + self::expression(rules, &e.left)
+                + self::expression(rules, &e.right)
+        },
+    }
+}
+
+impl fmt::Display for CostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "hardware and software: {}", self.fixed)?;
+        writeln!(f, "code that runs once: {}", self.once)?;
+        for l in &self.loops {
+            writeln!(
+                f,
+                "{} at line {}: {} per iteration",
+                l.kind, l.line, l.per_iteration
+            )?;
+        }
+        for b in &self.branches {
+            let branches: Vec<String> = b.branches.iter().map(ToString::to_string).collect();
+            writeln!(
+                f,
+                "als at line {}: condition {}, branches {}",
+                b.line,
+                b.condition,
+                branches.join(" / ")
+            )?;
+        }
+        if self.recursive {
+            writeln!(f, "recursive calls are only counted once")?;
+        }
+
+        write!(f, "total: {}", self.fixed + self.once)?;
+        for (i, l) in self.loops.iter().enumerate() {
+            write!(f, " + {} * n{}", l.per_iteration, i + 1)?;
+        }
+        if !self.loops.is_empty() {
+            let lines: Vec<String> = self
+                .loops
+                .iter()
+                .enumerate()
+                .map(|(i, l)| format!("n{} = iterations at line {}", i + 1, l.line))
+                .collect();
+            write!(f, "\n({})", lines.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Glade;
+
+    #[test]
+    fn matches_run() {
+        // the recursive call is never made and every als takes its most expensive
+        // branch, so the estimate is exact
+        let code = "gebruik a\ngebruik kompas\nfunctie tel {\n    als a > 100 {\n        roep tel\n    }\n    a = a + 1\n}\na = 0\nherhaal 3 keer {\n    roep tel\n}\nals a == 1 {\n    a = 5\n} anders als kompas == 1 {\n    a = a * 2 + 1\n} anders {\n    a = 0\n}";
+        let glade = Glade::parse_str("glade.csv", "s1;w;w;t1").expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        ctx.dialect = Dialect::Extended;
+        ctx.parse().expect("the code is valid");
+
+        let estimate = ctx.estimate();
+        assert!(estimate.recursive);
+        assert_eq!(
+            estimate.loops,
+            [LoopCost {
+                kind: "herhaal",
+                line: 10,
+                column: 1,
+                per_iteration: ctx.rules.herhaal_usage
+                    + ctx.rules.comparison_usage
+                    + ctx.rules.assignment_usage
+                    + ctx.rules.operation_usage,
+            }]
+        );
+        assert_eq!(
+            estimate
+                .branches
+                .iter()
+                .map(|b| (b.line, b.branches.len()))
+                .collect::<Vec<_>>(),
+            [(4, 2), (13, 2), (15, 2)]
+        );
+
+        ctx.execute().expect("the code runs");
+        assert_eq!(ctx.get_var("a"), Ok(7));
+        assert_eq!(
+            estimate.fixed + estimate.once + estimate.loops[0].per_iteration * 3,
+            ctx.points
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(file: &str, line: usize, column: usize) -> Self {
+        Self {
+            file: file.to_owned(),
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpretError {
+    Glade { location: Location, message: String },
+    Syntax { location: Location, message: String },
+    Semantic { location: Location, message: String },
+    Runtime { location: Location, message: String },
+    Rules { location: Location, message: String },
+    Io { location: Location, message: String },
+}
+
+impl InterpretError {
+    pub fn glade(location: Location, message: impl Into<String>) -> Self {
+        Self::Glade {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn syntax(location: Location, message: impl Into<String>) -> Self {
+        Self::Syntax {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn semantic(location: Location, message: impl Into<String>) -> Self {
+        Self::Semantic {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn runtime(location: Location, message: impl Into<String>) -> Self {
+        Self::Runtime {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn rules(location: Location, message: impl Into<String>) -> Self {
+        Self::Rules {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn io(location: Location, message: impl Into<String>) -> Self {
+        Self::Io {
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn location(&self) -> &Location {
+        match self {
+            Self::Glade {
+                location, ..
+            }
+            | Self::Syntax {
+                location, ..
+            }
+            | Self::Semantic {
+                location, ..
+            }
+            | Self::Runtime {
+                location, ..
+            }
+            | Self::Rules {
+                location, ..
+            }
+            | Self::Io {
+                location, ..
+            } => location,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Glade {
+                message, ..
+            }
+            | Self::Syntax {
+                message, ..
+            }
+            | Self::Semantic {
+                message, ..
+            }
+            | Self::Runtime {
+                message, ..
+            }
+            | Self::Rules {
+                message, ..
+            }
+            | Self::Io {
+                message, ..
+            } => message,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Glade {
+                ..
+            } => "glade error",
+            Self::Syntax {
+                ..
+            } => "syntax error",
+            Self::Semantic {
+                ..
+            } => "semantic error",
+            Self::Runtime {
+                ..
+            } => "runtime error",
+            Self::Rules {
+                ..
+            } => "rules error",
+            Self::Io {
+                ..
+            } => "io error",
+        }
+    }
+}
+
+impl fmt::Display for InterpretError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.location(),
+            self.kind(),
+            self.message()
+        )
+    }
+}
+
+impl std::error::Error for InterpretError {}
+
+pub type InterpretResult<T> = Result<T, InterpretError>;
@@ -0,0 +1,288 @@
+use super::{
+    error::{InterpretError, InterpretResult, Location},
+    map::{Glade, GladeState},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The characters the ascii format uses for numbered tiles, in the order
+/// they are handed out.
+const LEGEND_KEYS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%&*+?";
+
+/// The file formats a glade can be read from and written to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GladeFormat {
+    /// `;` separated tiles, one row per line.
+    Csv,
+    /// One character per tile, with a legend for the numbered tiles.
+    Ascii,
+    /// The tiles together with their width, height and metadata.
+    Json,
+}
+
+impl GladeFormat {
+    /// Picks the format from the extension of a path: `.json` is json,
+    /// `.glade` and `.txt` are ascii art and everything else is csv.
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("json") => Self::Json,
+            Some("glade" | "txt") => Self::Ascii,
+            _ => Self::Csv,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct JsonGlade {
+    width: usize,
+    height: usize,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, Value>,
+    tiles: Vec<Vec<String>>,
+}
+
+impl Glade {
+    /// Reads a glade in the format that belongs to the extension of `path`.
+    pub fn load(path: &str) -> InterpretResult<Self> {
+        let format = GladeFormat::from_path(path);
+        if format == GladeFormat::Csv {
+            return Self::parse(path);
+        }
+
+        let text = fs::read_to_string(path).map_err(|e| {
+            InterpretError::glade(
+                Location::new(path, 0, 0),
+                format!("unable to read glade file: {e}"),
+            )
+        })?;
+        match format {
+            GladeFormat::Ascii => Self::parse_ascii(path, &text),
+            _ => Self::parse_json(path, &text),
+        }
+    }
+
+    /// Writes a glade in the format that belongs to the extension of `path`.
+    pub fn save_as(&self, path: &str, state: GladeState) -> InterpretResult<()> {
+        let text = match GladeFormat::from_path(path) {
+            GladeFormat::Csv => return self.save(path, state),
+            GladeFormat::Ascii => self.to_ascii(state).ok_or_else(|| {
+                InterpretError::glade(
+                    Location::new(path, 0, 0),
+                    format!(
+                        "the glade has more than {} different numbered tiles to fit in a legend",
+                        LEGEND_KEYS.len()
+                    ),
+                )
+            })?,
+            GladeFormat::Json => self.to_json(state),
+        };
+
+        fs::write(path, text).map_err(|e| {
+            InterpretError::glade(
+                Location::new(path, 0, 0),
+                format!("unable to write glade file: {e}"),
+            )
+        })
+    }
+
+    /// Parses ascii art with one character per tile. The rows come first,
+    /// followed by an empty line and a legend of `A = t1` lines for the
+    /// tiles that need a number. Any other character is the tile itself.
+    pub fn parse_ascii(path: &str, text: &str) -> InterpretResult<Self> {
+        let mut lines = text.lines().map(str::trim_end).enumerate();
+        let grid: Vec<&str> = lines
+            .by_ref()
+            .take_while(|(_, line)| !line.is_empty())
+            .map(|(_, line)| line)
+            .collect();
+
+        let mut legend = BTreeMap::new();
+        for (i, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let location = Location::new(path, i + 1, 1);
+            let (key, token) = line
+                .split_once('=')
+                .map(|(key, token)| (key.trim(), token.trim()))
+                .ok_or_else(|| {
+                    InterpretError::glade(
+                        location.clone(),
+                        format!("expected a legend line like `A = t1`, found `{line}`"),
+                    )
+                })?;
+
+            let mut chars = key.chars();
+            let (Some(key), None) = (chars.next(), chars.next()) else {
+                return Err(InterpretError::glade(
+                    location,
+                    format!("a legend entry is a single character, found `{key}`"),
+                ));
+            };
+            if legend.insert(key, token).is_some() {
+                return Err(InterpretError::glade(
+                    location,
+                    format!("'{key}' is in the legend multiple times"),
+                ));
+            }
+        }
+
+        let rows = grid
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .map(|c| {
+                        legend
+                            .get(&c)
+                            .map_or_else(|| c.to_string(), |t| (*t).to_owned())
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::from_rows(path, rows)
+    }
+
+    /// Writes the glade as ascii art. Numbered tiles get a character from
+    /// the legend, which is `None` when there are too many different ones.
+    pub fn to_ascii(&self, state: GladeState) -> Option<String> {
+        let mut keys = LEGEND_KEYS.chars();
+        let mut legend: Vec<(char, String)> = Vec::new();
+        let mut art = String::new();
+
+        for row in self.to_rows(state) {
+            for token in row {
+                if token.len() == 1 {
+                    art.push_str(&token);
+                    continue;
+                }
+
+                let key = if let Some((key, _)) = legend.iter().find(|(_, t)| *t == token) {
+                    *key
+                } else {
+                    let key = keys.next()?;
+                    legend.push((key, token));
+                    key
+                };
+                art.push(key);
+            }
+            art.push('\n');
+        }
+
+        if !legend.is_empty() {
+            let lines: Vec<String> = legend
+                .iter()
+                .map(|(key, token)| format!("{key} = {token}"))
+                .collect();
+            art.push('\n');
+            art.push_str(&lines.join("\n"));
+            art.push('\n');
+        }
+        Some(art)
+    }
+
+    /// Parses a json object with the `width`, `height` and `tiles` of the
+    /// glade, and optionally a `metadata` object.
+    pub fn parse_json(path: &str, text: &str) -> InterpretResult<Self> {
+        let json: JsonGlade = serde_json::from_str(text).map_err(|e| {
+            InterpretError::glade(
+                Location::new(path, e.line(), e.column()),
+                format!("invalid json glade: {e}"),
+            )
+        })?;
+
+        if json.tiles.len() != json.height {
+            return Err(InterpretError::glade(
+                Location::new(path, 0, 0),
+                format!(
+                    "the glade has {} rows, but its height is {}",
+                    json.tiles.len(),
+                    json.height
+                ),
+            ));
+        }
+        if let Some((i, row)) = json
+            .tiles
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != json.width)
+        {
+            return Err(InterpretError::glade(
+                Location::new(path, i + 1, 0),
+                format!(
+                    "row {} has {} columns, but the width is {}",
+                    i + 1,
+                    row.len(),
+                    json.width
+                ),
+            ));
+        }
+
+        let mut glade = Self::from_rows(path, json.tiles)?;
+        glade.metadata = json.metadata;
+        Ok(glade)
+    }
+
+    /// Writes the glade as json.
+    pub fn to_json(&self, state: GladeState) -> String {
+        let json = JsonGlade {
+            width: self.width(),
+            height: self.height(),
+            metadata: self.metadata.clone(),
+            tiles: self.to_rows(state),
+        };
+        serde_json::to_string_pretty(&json).expect("a glade is valid json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collects two bonusses, arms a bomb and ends on a target.
+    fn played() -> Glade {
+        let mut glade = Glade::parse_str("glade.csv", "s1;m1;m2;x4;t1;t2\nw;w;w;w;w;w")
+            .expect("the test glade is valid");
+        for _ in 0..4 {
+            glade.forward().expect("the step is possible");
+        }
+        glade
+    }
+
+    const PLAYED: &str = "l;c;c;x4!3;s1+t1;t2\nw;w;w;w;w;w\n";
+
+    #[test]
+    fn ascii() {
+        let ascii = played()
+            .to_ascii(GladeState::Current)
+            .expect("the legend fits");
+        assert_eq!(ascii, "lccABC\nwwwwww\n\nA = x4!3\nB = s1+t1\nC = t2\n");
+
+        let parsed = Glade::parse_ascii("saved.glade", &ascii).expect("the saved glade is valid");
+        assert_eq!(parsed.to_csv(GladeState::Current), PLAYED);
+        assert_eq!(parsed.to_ascii(GladeState::Current), Some(ascii));
+    }
+
+    #[test]
+    fn json() {
+        let json = played().to_json(GladeState::Current);
+        let parsed = Glade::parse_json("saved.json", &json).expect("the saved glade is valid");
+        assert_eq!(parsed.to_csv(GladeState::Current), PLAYED);
+        assert_eq!(parsed.to_json(GladeState::Current), json);
+    }
+
+    #[test]
+    fn metadata() {
+        let text = r#"{"width": 3, "height": 1, "metadata": {"name": "corridor", "year": 2021, "authors": ["a", "b"]}, "tiles": [["s1", "w", "t1"]]}"#;
+        let glade = Glade::parse_json("glade.json", text).expect("the glade is valid");
+        assert_eq!(glade.metadata["year"], 2021);
+        assert_eq!(glade.metadata["authors"][1], "b");
+
+        let parsed = Glade::parse_json("saved.json", &glade.to_json(GladeState::Original))
+            .expect("the saved glade is valid");
+        assert_eq!(parsed.metadata, glade.metadata);
+        assert_eq!(parsed.to_csv(GladeState::Original), "s1;w;t1\n");
+    }
+}
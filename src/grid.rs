@@ -0,0 +1,134 @@
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid, stored row by row in a single `Vec`. Cells are
+/// indexed with `(x, y)`, where `x` is the column and `y` the row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order, there have to be
+    /// exactly `width * height` of them.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "a {width}x{height} grid needs {} cells",
+            width * height
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Replaces a cell, positions outside of the grid are ignored.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        if let Some(cell) = self.get_mut(x, y) {
+            *cell = value;
+        }
+    }
+
+    /// The cells directly north, east, south and west of `(x, y)` that are
+    /// inside the grid, in that order.
+    pub fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize, &T)> {
+        let candidates = [
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+            x.checked_sub(1).map(|x| (x, y)),
+        ];
+        IntoIterator::into_iter(candidates)
+            .flatten()
+            .filter_map(move |(x, y)| self.get(x, y).map(|c| (x, y, c)))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, c)| (i % width, i / width, c))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("the position is outside of the grid")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y)
+            .expect("the position is outside of the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbours(grid: &Grid<usize>, x: usize, y: usize) -> Vec<(usize, usize, usize)> {
+        grid.neighbours(x, y).map(|(x, y, c)| (x, y, *c)).collect()
+    }
+
+    #[test]
+    fn get_and_set() {
+        // every cell holds its own index
+        let mut grid = Grid::new(3, 2, (0..6).collect());
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get(usize::MAX, usize::MAX), None);
+
+        grid.set(1, 1, 40);
+        grid.set(3, 1, 50);
+        grid.set(1, 2, 60);
+        assert_eq!(grid, Grid::new(3, 2, vec![0, 1, 2, 3, 40, 5]));
+    }
+
+    #[test]
+    fn neighbours_in_order() {
+        let grid = Grid::new(3, 3, (0..9).collect());
+        assert_eq!(
+            neighbours(&grid, 1, 1),
+            [(1, 0, 1), (2, 1, 5), (1, 2, 7), (0, 1, 3)]
+        );
+        assert_eq!(neighbours(&grid, 0, 0), [(1, 0, 1), (0, 1, 3)]);
+        assert_eq!(neighbours(&grid, 2, 2), [(2, 1, 5), (1, 2, 7)]);
+        assert_eq!(neighbours(&grid, 2, 1), [(2, 0, 2), (2, 2, 8), (1, 1, 4)]);
+        assert_eq!(neighbours(&grid, 3, 3), []);
+    }
+}
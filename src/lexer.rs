@@ -0,0 +1,222 @@
+use super::error::{InterpretError, InterpretResult, Location};
+use std::{fmt, iter::Peekable, str::CharIndices};
+
+pub const KEYWORDS: &[&str] = &[
+    "gebruik",
+    "zolang",
+    "als",
+    "anders",
+    "print",
+    "stapVooruit",
+    "stapAchteruit",
+    "draaiLinks",
+    "draaiRechts",
+    "en",
+    "of",
+    "niet",
+    "functie",
+    "roep",
+    "herhaal",
+    "keer",
+    "breek",
+    "stop",
+];
+
+pub const HARDWARE_NAMES: &[&str] = &["kompas", "zwOog", "kleurOog"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Word(String),
+    Int(i32),
+    Assign,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    SmallerThan,
+    GreaterOrEqual,
+    SmallerOrEqual,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    LeftParen,
+    RightParen,
+    OpenBrace,
+    CloseBrace,
+    Newline,
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Word(w) => write!(f, "`{w}`"),
+            Self::Int(i) => write!(f, "number {i}"),
+            Self::Assign => write!(f, "`=`"),
+            Self::Equal => write!(f, "`==`"),
+            Self::NotEqual => write!(f, "`!=`"),
+            Self::GreaterThan => write!(f, "`>`"),
+            Self::SmallerThan => write!(f, "`<`"),
+            Self::GreaterOrEqual => write!(f, "`>=`"),
+            Self::SmallerOrEqual => write!(f, "`<=`"),
+            Self::Plus => write!(f, "`+`"),
+            Self::Minus => write!(f, "`-`"),
+            Self::Star => write!(f, "`*`"),
+            Self::Slash => write!(f, "`/`"),
+            Self::Percent => write!(f, "`%`"),
+            Self::LeftParen => write!(f, "`(`"),
+            Self::RightParen => write!(f, "`)`"),
+            Self::OpenBrace => write!(f, "`{{`"),
+            Self::CloseBrace => write!(f, "`}}`"),
+            Self::Newline => write!(f, "end of line"),
+            Self::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// A `//` comment, kept aside so the parser never sees it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+pub struct Lexer<'a> {
+    file: &'a str,
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    line: usize,
+    line_start: usize,
+    comments: Vec<Comment>,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(file: &'a str, text: &'a str) -> Self {
+        Self {
+            file,
+            text,
+            chars: text.char_indices().peekable(),
+            line: 1,
+            line_start: 0,
+            comments: Vec::new(),
+        }
+    }
+
+    pub fn tokenize(mut self) -> InterpretResult<(Vec<Token>, Vec<Comment>)> {
+        let mut tokens = Vec::new();
+
+        loop {
+            let token = self.next_token()?;
+            let done = token.kind == TokenKind::Eof;
+            tokens.push(token);
+            if done {
+                return Ok((tokens, self.comments));
+            }
+        }
+    }
+
+    fn column(&self, offset: usize) -> usize {
+        self.text[self.line_start..offset].chars().count() + 1
+    }
+
+    fn token(&self, kind: TokenKind, offset: usize) -> Token {
+        Token {
+            kind,
+            line: self.line,
+            column: self.column(offset),
+            offset,
+        }
+    }
+
+    fn error(&self, offset: usize, message: String) -> InterpretError {
+        InterpretError::syntax(
+            Location::new(self.file, self.line, self.column(offset)),
+            message,
+        )
+    }
+
+    fn take_while(&mut self, start: usize, f: impl Fn(char) -> bool) -> &'a str {
+        while self.chars.peek().is_some_and(|(_, c)| f(*c)) {
+            self.chars.next();
+        }
+        let end = self.chars.peek().map_or(self.text.len(), |(i, _)| *i);
+        &self.text[start..end]
+    }
+
+    fn next_is(&mut self, expected: char) -> bool {
+        if self.chars.peek().map(|(_, c)| *c) == Some(expected) {
+            self.chars.next();
+            return true;
+        }
+        false
+    }
+
+    fn next_token(&mut self) -> InterpretResult<Token> {
+        while let Some((_, ' ' | '\t' | '\r')) = self.chars.peek() {
+            self.chars.next();
+        }
+
+        let Some((offset, c)) = self.chars.next() else {
+            return Ok(self.token(TokenKind::Eof, self.text.len()));
+        };
+
+        if c == '/' && self.next_is('/') {
+            let text = self.take_while(offset, |c| c != '\n');
+            self.comments.push(Comment {
+                text: text[2..].trim_end().to_owned(),
+                line: self.line,
+                column: self.column(offset),
+            });
+            return self.next_token();
+        }
+
+        let kind =
+            match c {
+                '\n' => {
+                    let token = self.token(TokenKind::Newline, offset);
+                    self.line += 1;
+                    self.line_start = offset + 1;
+                    return Ok(token);
+                },
+                '0'..='9' => {
+                    let digits = self.take_while(offset, |c| c.is_ascii_digit());
+                    TokenKind::Int(digits.parse().map_err(|_| {
+                        self.error(offset, format!("the number {digits} is too large"))
+                    })?)
+                },
+                c if c.is_alphabetic() || c == '_' => TokenKind::Word(
+                    self.take_while(offset, |c| c.is_alphanumeric() || c == '_')
+                        .to_owned(),
+                ),
+                '=' if self.next_is('=') => TokenKind::Equal,
+                '=' => TokenKind::Assign,
+                '!' if self.next_is('=') => TokenKind::NotEqual,
+                '>' if self.next_is('=') => TokenKind::GreaterOrEqual,
+                '>' => TokenKind::GreaterThan,
+                '<' if self.next_is('=') => TokenKind::SmallerOrEqual,
+                '<' => TokenKind::SmallerThan,
+                '+' => TokenKind::Plus,
+                '-' => TokenKind::Minus,
+                '*' => TokenKind::Star,
+                '/' => TokenKind::Slash,
+                '%' => TokenKind::Percent,
+                '(' => TokenKind::LeftParen,
+                ')' => TokenKind::RightParen,
+                '{' => TokenKind::OpenBrace,
+                '}' => TokenKind::CloseBrace,
+                c => return Err(self.error(offset, format!("unexpected character '{c}'"))),
+            };
+
+        Ok(self.token(kind, offset))
+    }
+}
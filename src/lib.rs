@@ -0,0 +1,41 @@
+#![warn(clippy::pedantic)]
+#![allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::must_use_candidate,
+    clippy::semicolon_if_nothing_returned,
+    clippy::wildcard_imports
+)]
+
+pub mod check;
+pub mod cost;
+pub mod error;
+pub mod formats;
+pub mod grid;
+pub mod lexer;
+pub mod map;
+pub mod objects;
+pub mod outcome;
+pub mod parser;
+pub mod profile;
+pub mod render;
+pub mod rules;
+pub mod weights;
+
+pub use check::Warning;
+pub use cost::CostEstimate;
+pub use error::{InterpretError, InterpretResult, Location};
+pub use formats::GladeFormat;
+pub use grid::Grid;
+pub use map::{Content, Direction, Glade, GladeState, Griever};
+pub use objects::{Arithmetic, CodeBlock, Context, Dialect, LangObject, OnEvent, OnMove};
+pub use outcome::{Event, RunOutcome};
+pub use profile::{CostKind, Profile};
+pub use rules::{Colors, Rules};
+
+/// Parses and executes `program` against `glade`, returning how the run ended.
+pub fn run(program: &str, glade: Glade) -> InterpretResult<RunOutcome> {
+    let mut ctx = Context::new(program, glade);
+    ctx.parse()?;
+    ctx.execute()
+}
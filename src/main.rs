@@ -1,24 +1,79 @@
 #![warn(clippy::pedantic)]
-#![allow(dead_code)]
+#![allow(clippy::semicolon_if_nothing_returned)]
 
-mod map;
-mod objects;
-mod regex;
-mod weights;
+use windesheim_glade::{
+    Arithmetic,
+    Context,
+    Dialect,
+    Event,
+    Glade,
+    GladeState,
+    InterpretError,
+    Location,
+    Rules,
+};
 
-use map::Glade;
-use objects::Context;
+use std::rc::Rc;
+
+fn fail(e: &InterpretError) -> ! {
+    eprintln!("{e}");
+    std::process::exit(1)
+}
+
+fn read_instructions(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e| {
+        fail(&InterpretError::io(
+            Location::new(path, 0, 0),
+            format!("unable to read instructions file: {e}"),
+        ))
+    })
+}
+
+/// Prints what happens to the griever during the run, and draws the glade
+/// after every move when `render_steps` is set.
+fn print_progress(ctx: &mut Context, render_steps: bool) {
+    ctx.on_event = Some(Rc::new(|_: &Context, event: &Event| println!("{event}")));
+    if render_steps {
+        ctx.on_move = Some(Rc::new(|ctx: &Context| {
+            println!("{}\n", ctx.glade.render(&ctx.rules.colors))
+        }));
+    }
+}
 
 fn main() {
     let mut instructions_file: Option<String> = None;
     let mut glade_file: Option<String> = None;
     let mut next_glade = false;
     let mut next_instructions = false;
+    let mut dialect = Dialect::Standard;
+    let mut estimate = false;
+    let mut profile = false;
+    let mut charge_print = false;
+    let mut render = false;
+    let mut render_steps = false;
+    let mut next_limit = false;
+    let mut next_rules = false;
+    let mut rules_file: Option<String> = None;
+    let mut next_save = false;
+    let mut save_file: Option<String> = None;
+    let mut instruction_limit = None;
 
     for arg in std::env::args() {
         if next_glade {
             next_glade = false;
             glade_file = Some(arg)
+        } else if next_limit {
+            next_limit = false;
+            instruction_limit = Some(arg.parse().unwrap_or_else(|_| {
+                eprintln!("--max-instructions expects a number, found {arg}");
+                std::process::exit(1)
+            }))
+        } else if next_rules {
+            next_rules = false;
+            rules_file = Some(arg)
+        } else if next_save {
+            next_save = false;
+            save_file = Some(arg)
         } else if next_instructions {
             next_instructions = false;
             instructions_file = Some(arg)
@@ -26,20 +81,67 @@ fn main() {
             next_instructions = true
         } else if arg == "-g" {
             next_glade = true
+        } else if arg == "--taal20" {
+            dialect = Dialect::Taal20
+        } else if arg == "--extended" {
+            dialect = Dialect::Extended
+        } else if arg == "--estimate" {
+            estimate = true
+        } else if arg == "--save-glade" {
+            next_save = true
+        } else if arg == "--rules" {
+            next_rules = true
+        } else if arg == "--max-instructions" {
+            next_limit = true
+        } else if arg == "--charge-print" {
+            charge_print = true
+        } else if arg == "--profile" {
+            profile = true
+        } else if arg == "--render" {
+            render = true
+        } else if arg == "--render-steps" {
+            render_steps = true
         }
     }
 
-    let i_bytes: Vec<u8> =
-        std::fs::read(instructions_file.unwrap_or_else(|| String::from("./instructions.txt")))
-            .expect("no instructions file");
-    let instructions = String::from_utf8(i_bytes).unwrap();
-    let glade = Glade::parse(&glade_file.unwrap_or_else(|| String::from("./glade.csv")));
+    let instructions_file = instructions_file.unwrap_or_else(|| String::from("./instructions.txt"));
+    let instructions = read_instructions(&instructions_file);
+    let glade = Glade::load(&glade_file.unwrap_or_else(|| String::from("./glade.csv")))
+        .unwrap_or_else(|e| fail(&e));
 
     let mut ctx = Context::new(&instructions, glade);
+    ctx.file = instructions_file;
+    ctx.dialect = dialect;
+    if let Some(path) = rules_file {
+        ctx.rules = Rules::parse(&path).unwrap_or_else(|e| fail(&e));
+    }
+    if dialect == Dialect::Taal20 {
+        ctx.arithmetic = Arithmetic::Wrapping;
+    }
+    ctx.instruction_limit = instruction_limit;
+    ctx.charge_print = charge_print;
+    print_progress(&mut ctx, render_steps);
 
-    ctx.parse();
-    ctx.execute();
+    ctx.parse().unwrap_or_else(|e| fail(&e));
+    for warning in ctx.check() {
+        eprintln!("{warning}");
+    }
+    if estimate {
+        println!("{}", ctx.estimate());
+        return;
+    }
+    let outcome = ctx.execute().unwrap_or_else(|e| fail(&e));
 
-    println!("FAILED");
-    println!("points left: {}", 2020 - ctx.points);
+    if render {
+        println!("\n{}", ctx.glade.render(&ctx.rules.colors));
+    }
+    println!("\n{outcome}");
+    if let Some(path) = save_file {
+        ctx.glade
+            .save_as(&path, GladeState::Current)
+            .unwrap_or_else(|e| fail(&e));
+    }
+    if profile {
+        println!("\n{}", ctx.profile);
+    }
 }
@@ -1,12 +1,24 @@
-use super::weights::*;
+use super::{
+    error::{InterpretError, InterpretResult, Location},
+    grid::Grid,
+    rules::Colors,
+};
 use csv::ReaderBuilder;
 use rand::seq::IteratorRandom;
-use std::{collections::HashMap, convert::TryInto, iter::FromIterator};
-
-#[derive(Clone, Debug, PartialEq)]
+use std::{
+    collections::BTreeMap,
+    convert::TryInto,
+    fmt,
+    fs::File,
+    io::{self, Read, Write},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Content {
     Obstacle,
-    Bomb(i32, i32),
+    /// The seconds of the countdown and, once it has been stepped on, the
+    /// time it explodes at.
+    Bomb(i32, Option<i32>),
     WhiteSquare,
     GraySquare,
     RedSquare,
@@ -18,22 +30,37 @@ pub enum Content {
     BlackSquare,
     Griever(i32),
     Money(i32),
+    /// Money that has been picked up.
+    Collected,
     Turner(i32),
     Target(i32),
 }
 
 impl Content {
-    pub fn parse(text: &mut str) -> Self {
+    pub fn parse(text: &mut str) -> Result<Self, String> {
         text.make_ascii_lowercase();
-        let mut chars = text.chars();
-        let i = chars.next().unwrap();
-        let left = String::from_iter(chars.take_while(|c| c.is_numeric()));
-        match i {
+        let mut chars = text.trim().chars();
+        let i = chars
+            .next()
+            .ok_or_else(|| String::from("empty glade value"))?;
+        let left: String = chars.take_while(char::is_ascii_digit).collect();
+        let number = |name: &str| {
+            left.parse::<i32>()
+                .map_err(|_| format!("no value associated with the {name}"))
+        };
+
+        Ok(match i {
             'q' => Self::Obstacle,
-            'x' => Self::Bomb(
-                i32::from_str_radix(&left, 10).expect("no value associated with the bomb"),
-                0,
-            ),
+            'x' => {
+                let explodes = match text.trim().split_once('!') {
+                    Some((_, at)) => Some(
+                        at.parse()
+                            .map_err(|_| format!("invalid countdown '{at}' for the bomb"))?,
+                    ),
+                    None => None,
+                };
+                Self::Bomb(number("bomb")?, explodes)
+            },
             'w' => Self::WhiteSquare,
             'g' => Self::GraySquare,
             'r' => Self::RedSquare,
@@ -43,35 +70,33 @@ impl Content {
             'b' => Self::BlueSquare,
             'p' => Self::PurpleSquare,
             'l' => Self::BlackSquare,
-            't' => Self::Target(
-                i32::from_str_radix(&left, 10).expect("no value associated with the target") - 1,
-            ),
-            'm' => Self::Money(
-                i32::from_str_radix(&left, 10).expect("no value associated with the money"),
-            ),
-            'd' => Self::Turner(
-                i32::from_str_radix(&left, 10).expect("no value associated with the turner"),
-            ),
-            's' => Self::Griever(
-                i32::from_str_radix(&left, 10).expect("no value associated with the griever"),
-            ),
-            _ => panic!("invalid glade value"),
-        }
+            't' => match number("target")? {
+                0 => return Err(String::from("target number is not positive")),
+                t => Self::Target(t - 1),
+            },
+            'm' => Self::Money(number("money")?),
+            'c' => Self::Collected,
+            'd' => Self::Turner(number("turner")?),
+            's' => Self::Griever(number("griever")?),
+            _ => return Err(format!("invalid glade value '{text}'")),
+        })
     }
 
-    pub fn get_color_value(&self) -> i32 {
+    pub fn get_color_value(&self, colors: &Colors) -> i32 {
         match self {
             Self::BlackSquare | Self::Bomb(_, _) | Self::Griever(_) | Self::Obstacle => {
-                BLACK_SQUARE
+                colors.black
+            },
+            Self::YellowSquare | Self::Money(_) | Self::Collected | Self::Target(_) => {
+                colors.yellow
             },
-            Self::YellowSquare | Self::Money(_) | Self::Target(_) => YELLOW_SQUARE,
-            Self::BlueSquare | Self::Turner(_) => BLUE_SQUARE,
-            Self::GraySquare => GRAY_SQUARE,
-            Self::GreenSquare => GREEN_SQUARE,
-            Self::OrangeSquare => ORANGE_SQUARE,
-            Self::PurpleSquare => PURPLE_SQUARE,
-            Self::RedSquare => RED_SQUARE,
-            Self::WhiteSquare => WHITE_SQUARE,
+            Self::BlueSquare | Self::Turner(_) => colors.blue,
+            Self::GraySquare => colors.gray,
+            Self::GreenSquare => colors.green,
+            Self::OrangeSquare => colors.orange,
+            Self::PurpleSquare => colors.purple,
+            Self::RedSquare => colors.red,
+            Self::WhiteSquare => colors.white,
         }
     }
 }
@@ -85,106 +110,350 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn parse(i: i32) -> Self {
+    pub fn parse(i: i32) -> Option<Self> {
         match i {
-            0 => Self::North,
-            1 => Self::East,
-            2 => Self::South,
-            3 => Self::West,
-            _ => panic!("invalid direction value for griever"),
+            0 => Some(Self::North),
+            1 => Some(Self::East),
+            2 => Some(Self::South),
+            3 => Some(Self::West),
+            _ => None,
+        }
+    }
+}
+
+fn check_targets(path: &str, targets: &mut [i32]) -> InterpretResult<()> {
+    targets.sort_unstable();
+    for (i, t) in targets.iter().enumerate() {
+        if i != (*t).try_into().unwrap_or(usize::MAX) {
+            return Err(InterpretError::glade(
+                Location::new(path, 0, 0),
+                "missing one or more targets (note that targets have to be a continuos sequence starting at 1 and to a max of 9)",
+            ));
         }
     }
+    Ok(())
+}
+
+/// Parses the notation of a single tile, together with the direction of the
+/// griever when it stands on it. The griever stands on a black square, unless
+/// another tile follows after a `+` like `s1+t1`.
+fn parse_tile(location: &Location, text: &str) -> InterpretResult<(Content, Option<Direction>)> {
+    let parse = |text: &str| {
+        Content::parse(&mut text.to_owned()).map_err(|e| InterpretError::glade(location.clone(), e))
+    };
+    let (tile, under) = match text.split_once('+') {
+        Some((tile, under)) => (tile, Some(under)),
+        None => (text, None),
+    };
+
+    let s = match (parse(tile)?, under) {
+        (Content::Griever(s), _) => s,
+        (c, None) => return Ok((c, None)),
+        (_, Some(_)) => {
+            return Err(InterpretError::glade(
+                location.clone(),
+                format!("only the griever can stand on another tile, found '{text}'"),
+            ))
+        },
+    };
+    let direction = Direction::parse(s).ok_or_else(|| {
+        InterpretError::glade(
+            location.clone(),
+            format!("invalid direction value {s} for griever"),
+        )
+    })?;
+    let c = match under.map(parse).transpose()? {
+        Some(Content::Griever(_)) => {
+            return Err(InterpretError::glade(
+                location.clone(),
+                "there are multiple grievers in the glade",
+            ))
+        },
+        Some(c) => c,
+        None => Content::BlackSquare,
+    };
+    Ok((c, Some(direction)))
+}
+
+fn check_bonus(location: Location, v: i32, bonusses: &mut Vec<i32>) -> InterpretResult<()> {
+    if v > 30 {
+        return Err(InterpretError::glade(
+            location,
+            format!("a bonus of 2 ^ {v} is too large"),
+        ));
+    }
+    if bonusses.contains(&v) {
+        return Err(InterpretError::glade(
+            location,
+            format!("a bonus with value {v} exists multiple times"),
+        ));
+    }
+    bonusses.push(v);
+    Ok(())
+}
+
+/// Writes the notation `Content::parse` reads. A bomb that has been stepped on
+/// gets the time it explodes at after a `!`.
+impl fmt::Display for Content {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Obstacle => write!(f, "q"),
+            Self::Bomb(seconds, None) => write!(f, "x{seconds}"),
+            Self::Bomb(seconds, Some(at)) => write!(f, "x{seconds}!{at}"),
+            Self::WhiteSquare => write!(f, "w"),
+            Self::GraySquare => write!(f, "g"),
+            Self::RedSquare => write!(f, "r"),
+            Self::OrangeSquare => write!(f, "o"),
+            Self::YellowSquare => write!(f, "y"),
+            Self::GreenSquare => write!(f, "e"),
+            Self::BlueSquare => write!(f, "b"),
+            Self::PurpleSquare => write!(f, "p"),
+            Self::BlackSquare => write!(f, "l"),
+            Self::Griever(direction) => write!(f, "s{direction}"),
+            Self::Money(amount) => write!(f, "m{amount}"),
+            Self::Collected => write!(f, "c"),
+            Self::Turner(times) => write!(f, "d{times}"),
+            Self::Target(t) => write!(f, "t{}", t + 1),
+        }
+    }
+}
+
+/// Which version of a glade to write.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GladeState {
+    /// The glade as it was parsed.
+    Original,
+    /// The glade as it is now, with collected money and the griever at its
+    /// current position.
+    Current,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveError {
+    Obstacle,
+    OutOfBounds,
+    Exploded(usize, usize),
 }
 
 #[derive(Clone, Debug)]
 pub struct Glade {
-    pub map: HashMap<usize, HashMap<usize, Content>>,
+    pub map: Grid<Content>,
     pub griever: Griever,
+    /// Free-form information about the glade, such as its name or author.
+    /// Only the json format stores it.
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    /// Whether the griever is in the glade file, otherwise it starts at the
+    /// default position and is only written once it has moved.
+    has_griever: bool,
+    /// Whether a turner turns a random number of times.
+    random_turner: bool,
+    /// The time the last armed bomb explodes at.
+    armed_until: i32,
+    original: Grid<Content>,
+    start: Griever,
     seconds: i32,
     target_count: i32,
     last_target: i32,
 }
 
 impl Glade {
-    pub fn parse(path: &str) -> Self {
-        let mut targets: Vec<i32> = Vec::new();
-        let mut bonusses: Vec<i32> = Vec::new();
-        let mut griever = false;
+    pub fn parse(path: &str) -> InterpretResult<Self> {
+        let file = File::open(path).map_err(|e| {
+            InterpretError::glade(
+                Location::new(path, 0, 0),
+                format!("unable to read csv file: {e}"),
+            )
+        })?;
+        Self::parse_reader(path, file)
+    }
 
+    pub fn parse_str(path: &str, text: &str) -> InterpretResult<Self> {
+        Self::parse_reader(path, text.as_bytes())
+    }
+
+    fn parse_reader<R: Read>(path: &str, reader: R) -> InterpretResult<Self> {
         let mut csv_reader = ReaderBuilder::new()
             .has_headers(false)
             .delimiter(b';')
-            .from_path(path)
-            .expect("unable to read csv file");
-        let mut glade = Self {
-            map: HashMap::new(),
-            griever: Griever {
-                x: 1,
-                y: 1,
-                direction: Direction::North,
-            },
-            seconds: 0,
-            target_count: -1,
-            last_target: 0,
-        };
+            .flexible(true)
+            .from_reader(reader);
 
+        let mut rows = Vec::new();
         for (i, r_row) in csv_reader.records().enumerate() {
-            let row = r_row
-                .unwrap_or_else(|e| panic!("invalid row in csv table at line {}, err: {:?}", i, e));
-            glade.map.insert(i, HashMap::new());
-            for (j, r_column) in row.iter().enumerate() {
-                let m = glade.map.get_mut(&i).unwrap();
-                let mut column = String::from(r_column);
-                let mut c = Content::parse(&mut column);
+            let row = r_row.map_err(|e| {
+                let line = e
+                    .position()
+                    .map_or(i + 1, |p| p.line().try_into().unwrap_or(i + 1));
+                InterpretError::glade(
+                    Location::new(path, line, 0),
+                    format!("invalid row in csv table: {e}"),
+                )
+            })?;
+            rows.push(row.iter().map(String::from).collect());
+        }
+        Self::from_rows(path, rows)
+    }
 
+    /// Builds a glade from the notation of every tile, row by row. Errors
+    /// point at the row and column of the tile.
+    pub(crate) fn from_rows(path: &str, rows: Vec<Vec<String>>) -> InterpretResult<Self> {
+        let mut targets: Vec<i32> = Vec::new();
+        let mut bonusses: Vec<i32> = Vec::new();
+        let mut griever: Option<Griever> = None;
+        let mut random_turner = false;
+        let mut armed_until = 0;
+        let mut cells = Vec::new();
+        let (mut width, mut height) = (0, 0);
+
+        for (i, row) in rows.into_iter().enumerate() {
+            if i == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(InterpretError::glade(
+                    Location::new(path, i + 1, 0),
+                    format!(
+                        "row {} has {} columns, but the first row has {}",
+                        i + 1,
+                        row.len(),
+                        width
+                    ),
+                ));
+            }
+            for (j, column) in row.into_iter().enumerate() {
+                let location = Location::new(path, i + 1, j + 1);
+                let (c, direction) = parse_tile(&location, &column)?;
+
+                if let Some(direction) = direction {
+                    if griever.is_some() {
+                        return Err(InterpretError::glade(
+                            location,
+                            "there are multiple grievers in the glade",
+                        ));
+                    }
+                    griever = Some(Griever {
+                        x: j,
+                        y: i,
+                        direction,
+                    });
+                }
                 match c {
-                    Content::Griever(s) => {
-                        if griever {
-                            panic!("there are multiple grievers in the glade, this is an error!")
-                        }
-
-                        glade.griever = Griever {
-                            x: j,
-                            y: i,
-                            direction: Direction::parse(s),
-                        };
-                        c = Content::BlackSquare;
-                        griever = true;
-                    },
                     Content::Target(t) => {
                         if targets.contains(&t) {
-                            panic!("a target with value {} exists multiple times", t)
+                            return Err(InterpretError::glade(
+                                location,
+                                format!("a target with value {} exists multiple times", t + 1),
+                            ));
                         }
 
                         targets.push(t);
-                        if t > glade.last_target {
-                            glade.last_target = t
-                        }
-                    },
-                    Content::Money(v) => {
-                        if bonusses.contains(&v) {
-                            panic!("a bonus with value {} exists multiple times", v)
-                        }
-                        bonusses.push(v)
                     },
+                    Content::Money(v) => check_bonus(location, v, &mut bonusses)?,
+                    Content::Turner(0) => random_turner = true,
+                    Content::Bomb(_, Some(at)) => armed_until = armed_until.max(at),
                     _ => {},
                 }
 
-                m.insert(j, c);
+                cells.push(c);
             }
+            height += 1;
         }
 
-        targets.sort();
-        for (i, t) in targets.iter().enumerate() {
-            let target_num: usize = (*t)
-                .try_into()
-                .unwrap_or_else(|_| panic!("target number is not positive"));
-            if i != target_num {
-                panic!("missing one or more targets (note that targets have to be a continuos sequence starting at 1 and to a max of 9)")
-            }
+        if height == 0 || width == 0 {
+            return Err(InterpretError::glade(
+                Location::new(path, 0, 0),
+                "the glade is empty",
+            ));
+        }
+        check_targets(path, &mut targets)?;
+
+        let map = Grid::new(width, height, cells);
+        let has_griever = griever.is_some();
+        let griever = griever.unwrap_or(Griever {
+            x: 1,
+            y: 1,
+            direction: Direction::North,
+        });
+        if map.get(griever.x, griever.y).is_none() {
+            return Err(InterpretError::glade(
+                Location::new(path, 0, 0),
+                "the glade has no griever and is too small for the default position 2, 2",
+            ));
         }
+        Ok(Self {
+            has_griever,
+            random_turner,
+            armed_until,
+            original: map.clone(),
+            start: griever.clone(),
+            map,
+            griever,
+            metadata: BTreeMap::new(),
+            seconds: 0,
+            target_count: -1,
+            last_target: targets.iter().copied().max().unwrap_or(0),
+        })
+    }
 
-        glade
+    /// Writes the glade in the csv format it is parsed from.
+    pub fn write<W: Write>(&self, mut writer: W, state: GladeState) -> io::Result<()> {
+        for row in self.to_rows(state) {
+            writeln!(writer, "{}", row.join(";"))?;
+        }
+        Ok(())
+    }
+
+    /// The notation of every tile, row by row, with the griever in place.
+    /// Armed bombs explode at a time counted from now, so the glade can be
+    /// parsed again with the clock back at zero.
+    pub(crate) fn to_rows(&self, state: GladeState) -> Vec<Vec<String>> {
+        let (map, griever, now) = match state {
+            GladeState::Original => (&self.original, &self.start, 0),
+            GladeState::Current => (&self.map, &self.griever, self.seconds),
+        };
+        let position = |g: &Griever| (g.x, g.y, g.kompas());
+        let has_griever = self.has_griever || position(griever) != position(&self.start);
+
+        map.rows()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        let c = match c {
+                            Content::Bomb(seconds, Some(at)) => {
+                                Content::Bomb(*seconds, Some(at - now))
+                            },
+                            c => *c,
+                        };
+                        if !has_griever || (x, y) != (griever.x, griever.y) {
+                            c.to_string()
+                        } else if c == Content::BlackSquare {
+                            Content::Griever(griever.kompas()).to_string()
+                        } else {
+                            format!("{}+{}", Content::Griever(griever.kompas()), c)
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn to_csv(&self, state: GladeState) -> String {
+        let mut buffer = Vec::new();
+        self.write(&mut buffer, state)
+            .expect("writing to a Vec can't fail");
+        String::from_utf8(buffer).expect("the glade notation is ascii")
+    }
+
+    pub fn save(&self, path: &str, state: GladeState) -> InterpretResult<()> {
+        let error = |e: io::Error| {
+            InterpretError::glade(
+                Location::new(path, 0, 0),
+                format!("unable to write csv file: {e}"),
+            )
+        };
+        let file = File::create(path).map_err(error)?;
+        self.write(io::BufWriter::new(file), state).map_err(error)
     }
 
     fn s_inc(&mut self) {
@@ -192,74 +461,97 @@ impl Glade {
     }
 
     fn target_inc(&mut self, n: i32) {
-        println!("passed target {}", n + 1);
         if n - 1 == self.target_count {
             self.target_count += 1
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.map.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.map.height()
+    }
+
+    /// The number of steps taken, used for the countdown of bombs.
+    pub fn seconds(&self) -> i32 {
+        self.seconds
+    }
+
+    pub fn targets_reached(&self) -> i32 {
+        self.target_count + 1
+    }
+
+    /// Whether a bomb has been stepped on and can still explode.
+    pub fn has_armed_bomb(&self) -> bool {
+        self.armed_until > self.seconds
+    }
+
+    /// Whether the glade has a turner that turns a random number of times.
+    pub fn has_random_turner(&self) -> bool {
+        self.random_turner
+    }
+
     pub fn success(&self) -> bool {
         self.target_count != -1 && self.target_count == self.last_target
     }
 
-    fn get_forward(&self) -> (usize, usize) {
+    fn get_forward(&self) -> Result<(usize, usize), MoveError> {
+        let (x, y) = (self.griever.x, self.griever.y);
         match self.griever.direction {
-            Direction::North if self.griever.y > 0 => (self.griever.x, self.griever.y - 1),
-            Direction::East if self.griever.x < 19 => (self.griever.x + 1, self.griever.y),
-            Direction::South if self.griever.y < 19 => (self.griever.x, self.griever.y + 1),
-            Direction::West if self.griever.x > 0 => (self.griever.x - 1, self.griever.y),
-            _ => panic!("attempted to move out of the map!"),
+            Direction::North if y > 0 => Ok((x, y - 1)),
+            Direction::East if x + 1 < self.width() => Ok((x + 1, y)),
+            Direction::South if y + 1 < self.height() => Ok((x, y + 1)),
+            Direction::West if x > 0 => Ok((x - 1, y)),
+            _ => Err(MoveError::OutOfBounds),
         }
     }
 
-    fn get_backward(&self) -> (usize, usize) {
+    fn get_backward(&self) -> Result<(usize, usize), MoveError> {
+        let (x, y) = (self.griever.x, self.griever.y);
         match self.griever.direction {
-            Direction::North if self.griever.y < 19 => (self.griever.x, self.griever.y + 1),
-            Direction::East if self.griever.x > 0 => (self.griever.x - 1, self.griever.y),
-            Direction::South if self.griever.y > 0 => (self.griever.x, self.griever.y - 1),
-            Direction::West if self.griever.x < 19 => (self.griever.x + 1, self.griever.y),
-            _ => panic!("attempted to move out of the map!"),
+            Direction::North if y + 1 < self.height() => Ok((x, y + 1)),
+            Direction::East if x > 0 => Ok((x - 1, y)),
+            Direction::South if y > 0 => Ok((x, y - 1)),
+            Direction::West if x + 1 < self.width() => Ok((x + 1, y)),
+            _ => Err(MoveError::OutOfBounds),
         }
     }
 
-    fn get_pos(&mut self, x: usize, y: usize) -> Content {
-        self.map.get(&y).unwrap().get(&x).unwrap().clone()
+    pub fn get_pos(&self, x: usize, y: usize) -> Result<&Content, MoveError> {
+        self.map.get(x, y).ok_or(MoveError::OutOfBounds)
     }
 
     fn set_pos(&mut self, x: usize, y: usize, content: Content) {
-        self.map.get_mut(&y).unwrap().insert(x, content);
+        self.map.set(x, y, content);
     }
 
-    fn handle_new_pos(&mut self, x: usize, y: usize, c: &Content) -> Result<i32, ()> {
+    fn handle_new_pos(&mut self, x: usize, y: usize, c: &Content) -> Result<i32, MoveError> {
         // println!(
         //     "handling new position of {}, {}, direction: {:?}, content: {:?}",
         //     x, y, self.griever.direction, c
         // );
         match c {
             Content::Money(a) => {
-                self.set_pos(x, y, Content::Money(0));
-                let amount: u32 = (*a).try_into().unwrap_or_else(|_| {
-                    panic!(
-                        "the bonus amount is not a positive number at {}, {}",
-                        x + 1,
-                        y + 1
-                    )
-                });
+                self.set_pos(x, y, Content::Collected);
+                // the amount is validated to be between 0 and 30 when parsing the glade
+                let amount: u32 = (*a).try_into().unwrap_or(0);
                 return Ok(2_i32.pow(amount));
             },
-            Content::Bomb(seconds, last) => {
-                if *seconds == 0 || last + seconds == self.seconds {
-                    panic!(
-                        "\n------------\n\nBOOM!\nYou're dead at {}, {}\n\n------------\n",
-                        x + 1,
-                        y + 1
-                    )
-                } else if *last == 0 {
-                    self.set_pos(x, y, Content::Bomb(*seconds, self.seconds));
+            // the original tool gives 2 ^ 0 for money that is already picked up
+            Content::Collected => return Ok(1),
+            Content::Bomb(seconds, explodes) => {
+                if *seconds == 0 || *explodes == Some(self.seconds) {
+                    return Err(MoveError::Exploded(x, y));
+                } else if explodes.is_none() {
+                    let at = self.seconds + seconds;
+                    self.armed_until = self.armed_until.max(at);
+                    self.set_pos(x, y, Content::Bomb(*seconds, Some(at)));
                 }
             },
             Content::Target(times) => self.target_inc(*times),
-            Content::Obstacle => return Err(()),
+            Content::Obstacle => return Err(MoveError::Obstacle),
             Content::Turner(mut times) => {
                 if times == 0 {
                     let mut rng = rand::thread_rng();
@@ -270,7 +562,7 @@ impl Glade {
                 let mut i = 0;
                 while i < times {
                     i += 1;
-                    self.turn_right(true)
+                    self.turn_right(true)?;
                 }
             },
             _ => {},
@@ -278,10 +570,10 @@ impl Glade {
         Ok(0)
     }
 
-    pub fn forward(&mut self) -> Result<i32, ()> {
+    pub fn forward(&mut self) -> Result<i32, MoveError> {
         self.s_inc();
-        let f = self.get_forward();
-        let p = self.get_pos(f.0, f.1);
+        let f = self.get_forward()?;
+        let p = *self.get_pos(f.0, f.1)?;
 
         let res = self.handle_new_pos(f.0, f.1, &p);
         if res.is_ok() {
@@ -292,10 +584,10 @@ impl Glade {
         res
     }
 
-    pub fn backward(&mut self) -> Result<i32, ()> {
+    pub fn backward(&mut self) -> Result<i32, MoveError> {
         self.s_inc();
-        let b = self.get_backward();
-        let p = self.get_pos(b.0, b.1);
+        let b = self.get_backward()?;
+        let p = *self.get_pos(b.0, b.1)?;
 
         let res = self.handle_new_pos(b.0, b.1, &p);
         if res.is_ok() {
@@ -306,21 +598,17 @@ impl Glade {
         res
     }
 
-    pub fn bw_eye(&mut self) -> i32 {
-        let p = self.get_pos(self.griever.x, self.griever.y);
-        match p.get_color_value() {
-            1..=9 => 1,
-            0 => 0,
-            _ => panic!("negative aren't possible"),
-        }
+    pub fn bw_eye(&self, colors: &Colors) -> i32 {
+        let p = self.color_eye(colors);
+        i32::from(p > 0)
     }
 
-    pub fn color_eye(&mut self) -> i32 {
-        let p = self.get_pos(self.griever.x, self.griever.y);
-        p.get_color_value()
+    pub fn color_eye(&self, colors: &Colors) -> i32 {
+        self.get_pos(self.griever.x, self.griever.y)
+            .map_or(colors.black, |p| p.get_color_value(colors))
     }
 
-    pub fn turn_left(&mut self, auto: bool) {
+    pub fn turn_left(&mut self, auto: bool) -> Result<(), MoveError> {
         // println!("turn to the left");
         self.s_inc();
         self.griever.direction = match self.griever.direction {
@@ -330,13 +618,13 @@ impl Glade {
             Direction::West => Direction::South,
         };
         if !auto {
-            let c = self.get_pos(self.griever.x, self.griever.y);
-            self.handle_new_pos(self.griever.x, self.griever.y, &c)
-                .unwrap();
+            let c = *self.get_pos(self.griever.x, self.griever.y)?;
+            self.handle_new_pos(self.griever.x, self.griever.y, &c)?;
         }
+        Ok(())
     }
 
-    pub fn turn_right(&mut self, auto: bool) {
+    pub fn turn_right(&mut self, auto: bool) -> Result<(), MoveError> {
         // println!("turn to the right");
         self.s_inc();
         self.griever.direction = match self.griever.direction {
@@ -346,10 +634,10 @@ impl Glade {
             Direction::West => Direction::North,
         };
         if !auto {
-            let c = self.get_pos(self.griever.x, self.griever.y);
-            self.handle_new_pos(self.griever.x, self.griever.y, &c)
-                .unwrap();
+            let c = *self.get_pos(self.griever.x, self.griever.y)?;
+            self.handle_new_pos(self.griever.x, self.griever.y, &c)?;
         }
+        Ok(())
     }
 }
 
@@ -365,3 +653,106 @@ impl Griever {
         self.direction.clone() as i32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the glade and takes a number of steps forward.
+    fn walk(glade: &str, steps: usize) -> Glade {
+        let mut glade = Glade::parse_str("glade.csv", glade).expect("the test glade is valid");
+        for _ in 0..steps {
+            glade.forward().expect("the step is possible");
+        }
+        glade
+    }
+
+    /// Writes the current state and checks that parsing it gives the same
+    /// glade.
+    fn round_trip(glade: &Glade) -> (String, Glade) {
+        let csv = glade.to_csv(GladeState::Current);
+        let parsed = Glade::parse_str("saved.csv", &csv).expect("the saved glade is valid");
+        assert_eq!(parsed.to_csv(GladeState::Current), csv);
+        (csv, parsed)
+    }
+
+    #[test]
+    fn collected_money() {
+        let glade = walk("s1;m1;m2;w", 3);
+        let (csv, mut parsed) = round_trip(&glade);
+        assert_eq!(csv, "l;c;c;s1+w\n");
+        assert_eq!(glade.to_csv(GladeState::Original), "s1;m1;m2;w\n");
+
+        parsed.turn_left(false).expect("turning is possible");
+        parsed.turn_left(false).expect("turning is possible");
+        assert_eq!(parsed.forward(), Ok(1));
+    }
+
+    #[test]
+    fn griever_on_target() {
+        let glade = walk("s1;w;t1;t2", 2);
+        let (csv, parsed) = round_trip(&glade);
+        assert_eq!(csv, "l;w;s1+t1;t2\n");
+        assert_eq!(parsed.map[(2, 0)], Content::Target(0));
+        assert_eq!((parsed.griever.x, parsed.griever.y), (2, 0));
+    }
+
+    #[test]
+    fn armed_bomb() {
+        let glade = walk("s1;x4;w;w", 1);
+        let (csv, parsed) = round_trip(&glade);
+        assert_eq!(csv, "l;s1+x4!4;w;w\n");
+
+        // the bomb explodes 4 steps after it was stepped on, saved or not
+        let explodes = |mut glade: Glade| {
+            glade.forward().expect("the step is possible");
+            glade.turn_left(false).expect("turning is possible");
+            glade.turn_right(false).expect("turning is possible");
+            glade.backward()
+        };
+        assert_eq!(explodes(glade), Err(MoveError::Exploded(1, 0)));
+        assert_eq!(explodes(parsed), Err(MoveError::Exploded(1, 0)));
+    }
+
+    #[test]
+    fn without_griever() {
+        let glade = walk("t1;w\nw;w", 0);
+        assert_eq!(glade.to_csv(GladeState::Original), "t1;w\nw;w\n");
+        assert_eq!(round_trip(&glade).0, "t1;w\nw;w\n");
+
+        // once it has moved the griever is written, so it doesn't go back to the
+        // default
+        let mut glade = walk("w;w;w\nw;w;t1", 0);
+        glade.turn_right(false).expect("turning is possible");
+        glade.forward().expect("the step is possible");
+        let (csv, parsed) = round_trip(&glade);
+        assert_eq!(csv, "w;w;w\nw;w;s1+t1\n");
+        assert_eq!((parsed.griever.x, parsed.griever.y), (2, 1));
+        assert_eq!(glade.to_csv(GladeState::Original), "w;w;w\nw;w;t1\n");
+    }
+
+    #[test]
+    fn invalid_tiles() {
+        let error = |text| {
+            Glade::parse_str("glade.csv", text)
+                .expect_err("the glade is invalid")
+                .to_string()
+        };
+        assert_eq!(
+            error("w;t1+s1"),
+            "glade.csv:1:2: glade error: only the griever can stand on another tile, found 't1+s1'"
+        );
+        assert_eq!(
+            error("s1+s2;t1"),
+            "glade.csv:1:1: glade error: there are multiple grievers in the glade"
+        );
+        assert_eq!(
+            error("w;w;t1"),
+            "glade.csv:0:0: glade error: the glade has no griever and is too small for the default position 2, 2"
+        );
+        assert_eq!(
+            error("x3!a;t1"),
+            "glade.csv:1:1: glade error: invalid countdown 'a' for the bomb"
+        );
+    }
+}
@@ -1,16 +1,94 @@
-use super::{map::*, regex::*, weights::*};
-use std::collections::HashMap;
+use super::{
+    error::{InterpretError, InterpretResult, Location},
+    lexer::{Comment, Lexer, Token, TokenKind, HARDWARE_NAMES, KEYWORDS},
+    map::*,
+    outcome::{Event, ExecResult, Interrupt, RunOutcome},
+    parser::Parser,
+    profile::{CostKind, Profile},
+    rules::Rules,
+};
+use std::{collections::HashMap, fmt, rc::Rc};
 
 #[derive(Clone, Debug)]
 pub enum LangObject {
     Zolang(Zolang),
+    Herhaal(Herhaal),
     Als(Als),
     Assignment(Assignment),
     Print(Print),
-    StepForwards,
-    StepBackwards,
-    TurnLeft,
-    TurnRight,
+    Call(Call),
+    StepForwards { line: usize, column: usize },
+    StepBackwards { line: usize, column: usize },
+    TurnLeft { line: usize, column: usize },
+    TurnRight { line: usize, column: usize },
+    Break { line: usize, column: usize },
+    Stop { line: usize, column: usize },
+}
+
+impl LangObject {
+    pub fn position(&self) -> (usize, usize) {
+        match self {
+            Self::Zolang(v) => (v.line, v.column),
+            Self::Herhaal(v) => (v.line, v.column),
+            Self::Als(v) => (v.line, v.column),
+            Self::Assignment(v) => (v.line, v.column),
+            Self::Print(v) => (v.line, v.column),
+            Self::Call(v) => (v.line, v.column),
+            Self::StepForwards {
+                line,
+                column,
+            }
+            | Self::StepBackwards {
+                line,
+                column,
+            }
+            | Self::TurnLeft {
+                line,
+                column,
+            }
+            | Self::TurnRight {
+                line,
+                column,
+            }
+            | Self::Break {
+                line,
+                column,
+            }
+            | Self::Stop {
+                line,
+                column,
+            } => (*line, *column),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Zolang(_) => "zolang",
+            Self::Herhaal(_) => "herhaal",
+            Self::Als(_) => "als",
+            Self::Assignment(_) => "assignment",
+            Self::Print(_) => "print",
+            Self::Call(_) => "roep",
+            Self::StepForwards {
+                ..
+            } => "stapVooruit",
+            Self::StepBackwards {
+                ..
+            } => "stapAchteruit",
+            Self::TurnLeft {
+                ..
+            } => "draaiLinks",
+            Self::TurnRight {
+                ..
+            } => "draaiRechts",
+            Self::Break {
+                ..
+            } => "breek",
+            Self::Stop {
+                ..
+            } => "stop",
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -24,25 +102,29 @@ pub enum ExpressionVar {
 }
 
 impl ExpressionVar {
-    pub fn parse(text: &str, line: usize, ctx: &Context) -> Self {
-        if let Ok(i) = i32::from_str_radix(text.trim(), 10) {
-            Self::Int(i)
-        } else if text.trim().chars().count() == 1 {
-            if ctx.variables.get(text.trim()).is_none() {
-                panic!("variable {} is not defined at line {}", text.trim(), line)
-            }
-            Self::Variable(text.trim().to_owned())
-        } else if text.trim() == "kompas" && ctx.useable.contains(&Hardware::Kompas) {
-            Self::Kompas
-        } else if text.trim() == "zwOog" && ctx.useable.contains(&Hardware::ZwOog) {
-            Self::ZwOog
-        } else if text.trim() == "kleurOog" && ctx.useable.contains(&Hardware::KleurOog) {
-            Self::KleurOog
-        } else if INT_EXPRESSION.is_match(&text) {
-            Self::Expression(Box::new(IntExpression::parse(&text, line, ctx)))
-        } else {
-            panic!("invalid expression on line {}", line)
+    /// Calculates the value, charging the usage points of the sensors and
+    /// operations in it.
+    pub fn calc(&self, ctx: &mut Context) -> ExecResult<i32> {
+        self.value(ctx, true)
+    }
+
+    /// Calculates the value, only charging usage points when `charge` is set.
+    pub fn value(&self, ctx: &mut Context, charge: bool) -> ExecResult<i32> {
+        let (usage, value) = match self {
+            Self::Variable(name) => return Ok(ctx.get_var(name)?),
+            Self::Int(i) => return Ok(*i),
+            Self::Expression(e) => return e.value(ctx, charge),
+            Self::Kompas => (ctx.rules.kompas_usage, ctx.glade.griever.kompas()),
+            Self::KleurOog => (
+                ctx.rules.kleuroog_usage,
+                ctx.glade.color_eye(&ctx.rules.colors),
+            ),
+            Self::ZwOog => (ctx.rules.zwoog_usage, ctx.glade.bw_eye(&ctx.rules.colors)),
+        };
+        if charge {
+            ctx.add_points(usage)?;
         }
+        Ok(value)
     }
 }
 
@@ -52,6 +134,8 @@ pub enum Comparer {
     NotEqual,
     GreaterThan,
     SmallerThan,
+    GreaterOrEqual,
+    SmallerOrEqual,
 }
 
 #[derive(Clone, Debug)]
@@ -63,6 +147,18 @@ pub enum Operator {
     Remainder,
 }
 
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Product => "*",
+            Self::Divide => "/",
+            Self::Remainder => "%",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Hardware {
     Kompas,
@@ -70,234 +166,579 @@ pub enum Hardware {
     KleurOog,
 }
 
-#[derive(Clone, Debug)]
+impl Hardware {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Kompas => "kompas",
+            Self::ZwOog => "zwOog",
+            Self::KleurOog => "kleurOog",
+        }
+    }
+
+    pub fn cost(&self, rules: &Rules) -> i32 {
+        match self {
+            Self::Kompas => rules.kompas_hardware,
+            Self::ZwOog => rules.zwoog_hardware,
+            Self::KleurOog => rules.kleuroog_hardware,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SymbolKind {
+    Variable,
+    Hardware(Hardware),
+    Procedure,
+}
+
+impl SymbolKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Variable => "variable",
+            Self::Hardware(_) => "hardware",
+            Self::Procedure => "procedure",
+        }
+    }
+}
+
+/// A declaration made with `gebruik` or `functie`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Dialect {
+    /// The original taal 20 rules, operations are evaluated from left to right.
+    Taal20,
+    /// Taal 20 with operator precedence, parentheses and unary minus.
+    Standard,
+    /// The standard dialect plus the `en`, `of` and `niet` logical operators,
+    /// variable names longer than a single letter, procedures and `herhaal`.
+    Extended,
+}
+
+/// What happens when a calculation doesn't fit in a number or divides by zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arithmetic {
+    /// Stop the run with a runtime error.
+    Checked,
+    /// Behave like the original taal 20 tool, numbers wrap around and dividing
+    /// by zero results in 0.
+    Wrapping,
+}
+
+pub type OnMove = Rc<dyn Fn(&Context)>;
+pub type OnEvent = Rc<dyn Fn(&Context, &Event)>;
+
+#[derive(Clone)]
 pub struct Context {
+    pub file: String,
     pub file_text: String,
     pub code: CodeBlock,
     pub points: i32,
     pub variables: HashMap<String, Option<i32>>,
     pub useable: Vec<Hardware>,
+    pub symbols: HashMap<String, Symbol>,
+    pub comments: Vec<Comment>,
+    pub procedures: HashMap<String, Rc<Procedure>>,
     pub glade: Glade,
+    pub dialect: Dialect,
+    pub arithmetic: Arithmetic,
+    pub rules: Rules,
+    /// Whether the sensors and operations in a `print` cost usage points like
+    /// everywhere else, by default debug prints are free.
+    pub charge_print: bool,
+    /// Called after every step and turn, for example to draw the glade.
+    pub on_move: Option<OnMove>,
+    /// Called when the griever passes a target or collides with an obstacle.
+    pub on_event: Option<OnEvent>,
+    pub line: usize,
+    pub column: usize,
+    pub statement: &'static str,
+    pub profile: Profile,
+    pub collisions: u32,
+    pub collision_limit: Option<u32>,
+    pub instructions: u64,
+    pub instruction_limit: Option<u64>,
+    pub call_depth: usize,
+    pub max_call_depth: usize,
 }
 
 impl Context {
     pub fn new(file_text: &str, glade: Glade) -> Self {
         Self {
+            file: String::from("instructions.txt"),
             file_text: file_text.to_owned(),
-            code: CodeBlock {
-                text: file_text.to_owned(),
-                line: 0,
-                objects: Vec::new(),
-            },
+            code: CodeBlock::default(),
             points: 0,
             variables: HashMap::new(),
             useable: Vec::new(),
+            symbols: HashMap::new(),
+            comments: Vec::new(),
+            procedures: HashMap::new(),
             glade,
+            dialect: Dialect::Standard,
+            arithmetic: Arithmetic::Checked,
+            rules: Rules::default(),
+            charge_print: false,
+            on_move: None,
+            on_event: None,
+            line: 0,
+            column: 0,
+            statement: "",
+            profile: Profile::default(),
+            collisions: 0,
+            collision_limit: None,
+            instructions: 0,
+            instruction_limit: None,
+            call_depth: 0,
+            max_call_depth: 100,
         }
     }
 
-    pub fn parse(&mut self) {
-        self.parse_variables();
-        self.code = CodeBlock::parse(self.file_text.clone(), 0, self);
-    }
-
-    fn parse_variables(&mut self) {
-        let text = self.file_text.clone();
-        let lines: Vec<&str> = text.split('\n').collect();
-
-        for line in &lines {
-            if INSTANTIATOR.is_match(line) {
-                let c = INSTANTIATOR.captures(line).unwrap();
-                let name = c.get(1).unwrap().as_str().to_owned();
-
-                match name.as_ref() {
-                    "kompas" => {
-                        self.useable.push(Hardware::Kompas);
-                        self.add_points(KOMPAS_HARDWARE);
-                        continue;
-                    },
-                    "zwOog" => {
-                        self.useable.push(Hardware::ZwOog);
-                        self.add_points(ZWOOG_HARDWARE);
-                        continue;
-                    },
-                    "kleurOog" => {
-                        self.useable.push(Hardware::KleurOog);
-                        self.add_points(KLEUROOG_HARDWARE);
-                        continue;
-                    },
-                    _ => {},
+    pub fn parse(&mut self) -> InterpretResult<()> {
+        let (tokens, comments) = Lexer::new(&self.file, &self.file_text).tokenize()?;
+        self.comments = comments;
+        self.parse_variables(&tokens)?;
+        self.parse_procedures(&tokens)?;
+        self.code = Parser::new(tokens, self).parse()?;
+        Ok(())
+    }
+
+    /// All `gebruik` statements are handled before the rest of the program,
+    /// so variables and hardware can be used anywhere in the file.
+    fn parse_variables(&mut self, tokens: &[Token]) -> InterpretResult<()> {
+        for (i, token) in tokens.iter().enumerate() {
+            if token.kind != TokenKind::Word(String::from("gebruik")) {
+                continue;
+            }
+
+            let token = &tokens[i + 1];
+            let location = self.at(token.line, token.column);
+            let name = match &token.kind {
+                TokenKind::Word(name) => name.clone(),
+                kind => {
+                    return Err(InterpretError::syntax(
+                        location,
+                        format!("expected a variable or hardware name, found {kind}"),
+                    ))
+                },
+            };
+            self.set_position(token.line, token.column, "gebruik");
+
+            let kind = match name.as_str() {
+                "kompas" => SymbolKind::Hardware(Hardware::Kompas),
+                "zwOog" => SymbolKind::Hardware(Hardware::ZwOog),
+                "kleurOog" => SymbolKind::Hardware(Hardware::KleurOog),
+                _ => {
+                    self.check_name(&name, &SymbolKind::Variable)
+                        .map_err(|e| InterpretError::semantic(location.clone(), e))?;
+                    SymbolKind::Variable
+                },
+            };
+
+            let cost = match &kind {
+                SymbolKind::Hardware(h) => h.cost(&self.rules),
+                _ => self.rules.var_hardware,
+            };
+            if let Some(previous) = self.symbols.get(&name) {
+                // taal 20 accepts declaring the same name again and charges it every time
+                if self.dialect != Dialect::Extended {
+                    self.charge(CostKind::Hardware, cost);
+                    continue;
                 }
+                return Err(InterpretError::semantic(
+                    location,
+                    format!("`{name}` is already declared at line {}", previous.line),
+                ));
+            }
 
-                self.variables.insert(name, None);
-                self.add_points(VAR_HARDWARE);
+            if let SymbolKind::Hardware(h) = &kind {
+                self.useable.push(h.clone());
+            } else {
+                self.variables.insert(name.clone(), None);
             }
+            self.charge(CostKind::Hardware, cost);
+            self.symbols.insert(
+                name,
+                Symbol {
+                    kind,
+                    line: token.line,
+                    column: token.column,
+                },
+            );
         }
+
+        Ok(())
     }
 
-    pub fn execute(&mut self) {
-        let code = self.code.clone();
-        code.execute(self);
-        self.code = code;
+    /// Procedures are registered up front, so they can be called before the
+    /// place where they are declared.
+    fn parse_procedures(&mut self, tokens: &[Token]) -> InterpretResult<()> {
+        if self.dialect != Dialect::Extended {
+            return Ok(());
+        }
+
+        for (i, token) in tokens.iter().enumerate() {
+            if token.kind != TokenKind::Word(String::from("functie")) {
+                continue;
+            }
+
+            let token = &tokens[i + 1];
+            let location = self.at(token.line, token.column);
+            let TokenKind::Word(name) = &token.kind else {
+                return Err(InterpretError::syntax(
+                    location,
+                    format!("expected a procedure name, found {}", token.kind),
+                ));
+            };
+
+            self.check_name(name, &SymbolKind::Procedure)
+                .map_err(|e| InterpretError::semantic(location.clone(), e))?;
+            if let Some(previous) = self.symbols.get(name) {
+                return Err(InterpretError::semantic(
+                    location,
+                    format!("`{name}` is already declared at line {}", previous.line),
+                ));
+            }
+
+            self.symbols.insert(
+                name.clone(),
+                Symbol {
+                    kind: SymbolKind::Procedure,
+                    line: token.line,
+                    column: token.column,
+                },
+            );
+        }
+
+        Ok(())
     }
 
-    pub fn get_var(&self, name: &str) -> i32 {
-        if let Some(v) = self.variables.get(name) {
-            if let Some(i) = v {
-                return *i;
+    /// Checks whether `name` can be declared as a variable or procedure.
+    fn check_name(&self, name: &str, kind: &SymbolKind) -> Result<(), String> {
+        let what = kind.name();
+        if KEYWORDS.contains(&name) {
+            return Err(format!(
+                "`{name}` is a keyword and can't be used as a {what} name"
+            ));
+        }
+        if let Some(hardware) = HARDWARE_NAMES.iter().find(|h| h.eq_ignore_ascii_case(name)) {
+            return Err(format!(
+                "`{name}` collides with the hardware name {hardware}"
+            ));
+        }
+
+        let mut chars = name.chars();
+        let starts_lowercase = chars.next().is_some_and(|c| c.is_ascii_lowercase());
+        if self.dialect == Dialect::Extended {
+            if !starts_lowercase || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("`{name}` is not a valid {what} name, {what}s start with a lowercase letter followed by letters, digits or underscores"));
             }
+        } else if !starts_lowercase || chars.next().is_some() {
+            return Err(format!("`{name}` is not a valid {what} name, {what}s have to be a single lowercase letter (longer names are available in the extended dialect)"));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_variable_name(&self, name: &str) -> bool {
+        self.check_name(name, &SymbolKind::Variable).is_ok()
+    }
+
+    pub fn execute(&mut self) -> InterpretResult<RunOutcome> {
+        if self.points > self.rules.budget {
+            return Ok(RunOutcome::OverBudget {
+                points: self.points,
+            });
+        }
+
+        // the code is moved out while it runs, so it doesn't have to be cloned
+        let code = std::mem::take(&mut self.code);
+        let res = code.execute(self);
+        self.code = code;
+
+        match res {
+            Ok(()) => Ok(RunOutcome::Failed {
+                targets_reached: self.glade.targets_reached(),
+                points: self.points,
+                budget: self.rules.budget,
+            }),
+            Err(Interrupt::Halt(outcome)) => Ok(outcome),
+            Err(Interrupt::Error(e)) => Err(e),
+            Err(Interrupt::Break) => Err(InterpretError::runtime(
+                self.location(),
+                "`breek` can only be used inside a loop",
+            )),
+        }
+    }
+
+    pub fn at(&self, line: usize, column: usize) -> Location {
+        Location::new(&self.file, line, column)
+    }
+
+    pub fn location(&self) -> Location {
+        self.at(self.line, self.column)
+    }
+
+    /// Sets the statement that errors and costs are attributed to.
+    pub fn set_position(&mut self, line: usize, column: usize, statement: &'static str) {
+        self.line = line;
+        self.column = column;
+        self.statement = statement;
+    }
+
+    pub fn get_var(&self, name: &str) -> InterpretResult<i32> {
+        match self.variables.get(name) {
+            Some(Some(i)) => Ok(*i),
+            Some(None) => Err(InterpretError::runtime(
+                self.location(),
+                format!("variable {name} is used before it is assigned a value"),
+            )),
+            None => Err(InterpretError::runtime(
+                self.location(),
+                format!("no variable named {name} defined"),
+            )),
         }
-        panic!("no variable named {} defined", name)
     }
 
-    pub fn add_points(&mut self, p: i32) {
+    pub fn charge(&mut self, kind: CostKind, p: i32) {
         self.points += p;
+        self.profile.record(self.line, self.statement, kind, p);
         // println!("points: {}", self.points);
-        if self.points > 2020 {
-            panic!(
-                "Used up too much of your money! your total expenses are: {}",
-                self.points
-            )
+    }
+
+    pub fn add_points(&mut self, p: i32) -> ExecResult<()> {
+        self.spend(CostKind::Usage, p)
+    }
+
+    fn spend(&mut self, kind: CostKind, p: i32) -> ExecResult<()> {
+        self.charge(kind, p);
+        if self.points > self.rules.budget {
+            return Err(Interrupt::Halt(RunOutcome::OverBudget {
+                points: self.points,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Counts a single executed statement or loop iteration.
+    fn tick(&mut self) -> ExecResult<()> {
+        self.instructions += 1;
+        if self
+            .instruction_limit
+            .is_some_and(|l| self.instructions > l)
+        {
+            return Err(Interrupt::Halt(RunOutcome::InstructionLimit {
+                instructions: self.instructions - 1,
+                points: self.points,
+                budget: self.rules.budget,
+            }));
         }
+        Ok(())
+    }
+
+    fn loop_state(&self) -> LoopState {
+        let mut variables: Vec<(String, Option<i32>)> = self
+            .variables
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        variables.sort();
+        LoopState {
+            x: self.glade.griever.x,
+            y: self.glade.griever.y,
+            direction: self.glade.griever.kompas(),
+            targets_reached: self.glade.targets_reached(),
+            seconds: self.glade.has_armed_bomb().then(|| self.glade.seconds()),
+            variables,
+        }
+    }
+
+    fn moved(&self) {
+        if let Some(on_move) = &self.on_move {
+            on_move(self);
+        }
+    }
+
+    fn report(&self, event: &Event) {
+        if let Some(on_event) = &self.on_event {
+            on_event(self, event);
+        }
+    }
+
+    /// Reports the target the griever stands on after a move or turn, like the
+    /// glade counts it.
+    fn arrived(&self) {
+        let griever = &self.glade.griever;
+        if let Ok(Content::Target(n)) = self.glade.get_pos(griever.x, griever.y) {
+            self.report(&Event::Target(*n));
+        }
+    }
+
+    fn move_error(&self, e: &MoveError) -> Interrupt {
+        match e {
+            MoveError::Exploded(x, y) => Interrupt::Halt(RunOutcome::Exploded {
+                x: *x,
+                y: *y,
+                points: self.points,
+            }),
+            MoveError::Obstacle => Interrupt::Error(InterpretError::runtime(
+                self.location(),
+                "collided against an obstacle",
+            )),
+            MoveError::OutOfBounds => Interrupt::Error(InterpretError::runtime(
+                self.location(),
+                "attempted to move out of the map",
+            )),
+        }
+    }
+
+    fn step(&mut self, res: Result<i32, MoveError>) -> ExecResult<()> {
+        self.moved();
+        match res {
+            Ok(0) => self.arrived(),
+            Ok(a) => {
+                self.arrived();
+                self.charge(CostKind::Bonus, -a);
+            },
+            Err(MoveError::Obstacle) => {
+                let griever = &self.glade.griever;
+                self.report(&Event::Collision {
+                    x: griever.x,
+                    y: griever.y,
+                    direction: griever.direction.clone(),
+                });
+                self.collisions += 1;
+                self.spend(CostKind::Collision, self.rules.push_obstacle)?;
+                if self.collision_limit.is_some_and(|l| self.collisions >= l) {
+                    return Err(Interrupt::Halt(RunOutcome::CollisionLimit {
+                        collisions: self.collisions,
+                        points: self.points,
+                        budget: self.rules.budget,
+                    }));
+                }
+            },
+            Err(e) => return Err(self.move_error(&e)),
+        }
+        if self.glade.success() {
+            return Err(Interrupt::Halt(RunOutcome::Success {
+                points: self.points,
+                budget: self.rules.budget,
+            }));
+        }
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug)]
+/// The callbacks can't be printed, so only whether they are set is shown.
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("file", &self.file)
+            .field("file_text", &self.file_text)
+            .field("code", &self.code)
+            .field("points", &self.points)
+            .field("variables", &self.variables)
+            .field("useable", &self.useable)
+            .field("symbols", &self.symbols)
+            .field("comments", &self.comments)
+            .field("procedures", &self.procedures)
+            .field("glade", &self.glade)
+            .field("dialect", &self.dialect)
+            .field("arithmetic", &self.arithmetic)
+            .field("rules", &self.rules)
+            .field("charge_print", &self.charge_print)
+            .field("on_move", &self.on_move.is_some())
+            .field("on_event", &self.on_event.is_some())
+            .field("line", &self.line)
+            .field("column", &self.column)
+            .field("statement", &self.statement)
+            .field("profile", &self.profile)
+            .field("collisions", &self.collisions)
+            .field("collision_limit", &self.collision_limit)
+            .field("instructions", &self.instructions)
+            .field("instruction_limit", &self.instruction_limit)
+            .field("call_depth", &self.call_depth)
+            .field("max_call_depth", &self.max_call_depth)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct CodeBlock {
     pub objects: Vec<LangObject>,
-    pub text: String,
     pub line: usize,
 }
 
 impl CodeBlock {
-    pub fn parse(text: String, line_nr: usize, ctx: &mut Context) -> Self {
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut objects: Vec<LangObject> = Vec::new();
-        let mut open_brackets = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            if open_brackets > 0 {
-                if line.contains('}') {
-                    open_brackets -= 1;
-                }
-                if line.contains('{') {
-                    open_brackets += 1;
-                }
-            } else if FORBIDDEN_END_BLOCK.is_match(&line) {
-                panic!(
-                    "a closing }} has to be on a newline! it isn't at line {}",
-                    line_nr + i + 1
-                )
-            } else if HANGING_EXPRESSION.is_match(&line) {
-                panic!(
-                    "an operator or comparer needs something to operate with or compare to! it doesn't at line {}",
-                    line_nr + i + 1
-                )
-            } else if line.trim() == "draaiLinks" {
-                ctx.add_points(ACTION_SOFTWARE);
-                objects.push(LangObject::TurnLeft)
-            } else if line.trim() == "draaiRechts" {
-                ctx.add_points(ACTION_SOFTWARE);
-                objects.push(LangObject::TurnRight)
-            } else if line.trim() == "stapVooruit" {
-                ctx.add_points(ACTION_SOFTWARE);
-                objects.push(LangObject::StepForwards)
-            } else if line.trim() == "stapAchteruit" {
-                ctx.add_points(ACTION_SOFTWARE);
-                objects.push(LangObject::StepBackwards)
-            } else if ASSIGNMENT.is_match(line) {
-                objects.push(Assignment::parse(*line, line_nr + i + 1, ctx))
-            } else if PRINT.is_match(line) {
-                objects.push(Print::parse(*line, line_nr + i + 1, ctx))
-            } else if String::from(*line).trim().is_empty() {
-                continue;
-            } else if ZOLANG_ID.is_match(line) {
-                let mut full: String = String::new();
-                lines.iter().enumerate().for_each(|(k, x)| {
-                    if k >= i {
-                        full.push_str(*x);
-                        full.push_str("\n");
-                    }
-                });
-                open_brackets += 1;
-                println!("parsing zolang");
-                objects.push(Zolang::parse(&full, line_nr + i + 1, ctx));
-            } else if ALS_ID.is_match(line) {
-                let mut full: String = String::new();
-                lines.iter().enumerate().for_each(|(k, x)| {
-                    if k >= i {
-                        full.push_str(*x);
-                        full.push_str("\n");
-                    }
-                });
-                open_brackets += 1;
-                println!("parsing als");
-                objects.push(Als::parse(&full, line_nr + i + 1, ctx));
-            } else if INSTANTIATOR.is_match(line) {
-                continue;
-            } else {
-                panic!("\ninvalid text on line {}: {}\n", line_nr + i + 1, line)
-            }
-        }
-
-        Self {
-            objects,
-            text,
-            line: line_nr,
-        }
-    }
-
-    pub fn execute(&self, ctx: &mut Context) {
+    pub fn execute(&self, ctx: &mut Context) -> ExecResult<()> {
         for obj in &self.objects {
+            let (line, column) = obj.position();
+            ctx.set_position(line, column, obj.kind());
+            ctx.tick()?;
+
             match obj {
-                LangObject::Zolang(v) => v.run_loop(ctx),
-                LangObject::Assignment(v) => v.calc(ctx),
-                LangObject::Als(v) => v.run_if(ctx),
-                LangObject::Print(v) => v.print(ctx),
-                LangObject::TurnLeft => {
-                    ctx.glade.turn_left(false);
-                    ctx.add_points(TURNLEFT_USAGE);
+                LangObject::Zolang(v) => v.run_loop(ctx)?,
+                LangObject::Herhaal(v) => v.run_loop(ctx)?,
+                LangObject::Assignment(v) => v.calc(ctx)?,
+                LangObject::Als(v) => v.run_if(ctx)?,
+                LangObject::Print(v) => v.print(ctx)?,
+                LangObject::Call(v) => v.call(ctx)?,
+                LangObject::TurnLeft {
+                    ..
+                } => {
+                    ctx.glade.turn_left(false).map_err(|e| ctx.move_error(&e))?;
+                    ctx.moved();
+                    ctx.arrived();
+                    ctx.add_points(ctx.rules.turnleft_usage)?;
                 },
-                LangObject::TurnRight => {
-                    ctx.glade.turn_right(false);
-                    ctx.add_points(TURNRIGHT_USAGE);
+                LangObject::TurnRight {
+                    ..
+                } => {
+                    ctx.glade
+                        .turn_right(false)
+                        .map_err(|e| ctx.move_error(&e))?;
+                    ctx.moved();
+                    ctx.arrived();
+                    ctx.add_points(ctx.rules.turnright_usage)?;
                 },
-                LangObject::StepForwards => {
+                LangObject::StepForwards {
+                    ..
+                } => {
                     // println!("step forward");
-                    match ctx.glade.forward() {
-                        Result::Ok(a) => ctx.points -= a,
-                        Result::Err(_) => {
-                            println!(
-                                "WARNING: collided against obstacle! location: {}, {}, direction: {:?}",
-                                ctx.glade.griever.x + 1, ctx.glade.griever.y + 1, ctx.glade.griever.direction
-                            );
-                            ctx.add_points(PUSH_OBSTACLE)
-                        },
-                    };
-                    if ctx.glade.success() {
-                        println!("\nSUCCESS!\ncosts: {}", 2020 - ctx.points);
-                        std::process::exit(0)
-                    };
+                    ctx.add_points(ctx.rules.stepforward_usage)?;
+                    let res = ctx.glade.forward();
+                    ctx.step(res)?;
                 },
-                LangObject::StepBackwards => {
+                LangObject::StepBackwards {
+                    ..
+                } => {
                     // println!("step backwards");
-                    match ctx.glade.backward() {
-                        Result::Ok(a) => ctx.points -= a,
-                        Result::Err(_) => {
-                            println!(
-                                "WARNING: collided against obstacle! location: {}, {}, direction: {:?}",
-                                ctx.glade.griever.x + 1, ctx.glade.griever.y + 1, ctx.glade.griever.direction
-                            );
-                            ctx.add_points(PUSH_OBSTACLE)
-                        },
-                    };
-                    if ctx.glade.success() {
-                        println!("\nSUCCESS!\ncosts: {}", 2020 - ctx.points);
-                        std::process::exit(0)
-                    };
+                    ctx.add_points(ctx.rules.stepbackwards_usage)?;
+                    let res = ctx.glade.backward();
+                    ctx.step(res)?;
+                },
+                LangObject::Break {
+                    ..
+                } => return Err(Interrupt::Break),
+                LangObject::Stop {
+                    line, ..
+                } => {
+                    return Err(Interrupt::Halt(RunOutcome::Stopped {
+                        line: *line,
+                        targets_reached: ctx.glade.targets_reached(),
+                        points: ctx.points,
+                        budget: ctx.rules.budget,
+                    }))
                 },
             }
         }
+
+        Ok(())
     }
 }
 
@@ -310,75 +751,40 @@ pub struct BoolExpression {
 }
 
 impl BoolExpression {
-    pub fn parse(text: &str, line: usize, ctx: &Context) -> Self {
-        let c = BOOL_EXPRESSION
-            .captures(&text)
-            .unwrap_or_else(|| panic!("there is an error in the bool expression at line {}", line));
-        let left_str = c.get(1).unwrap().as_str();
-        let right_str = c.get(3).unwrap().as_str();
-
-        let comparer = match c.get(2).unwrap().as_str() {
-            "==" => Comparer::Equal,
-            "!=" => Comparer::NotEqual,
-            ">" => Comparer::GreaterThan,
-            "<" => Comparer::SmallerThan,
-            _ => panic!("unknown comparer"),
-        };
-
-        Self {
-            left: ExpressionVar::parse(left_str, line, ctx),
-            right: ExpressionVar::parse(right_str, line, ctx),
-            line,
-            comparer,
-        }
-    }
-
-    pub fn calc(&self, ctx: &mut Context) -> bool {
-        ctx.add_points(COMPARISON_USAGE);
-        let left = match &self.left {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Int(var) => *var,
-            ExpressionVar::Expression(deeper) => deeper.calc(ctx),
-            ExpressionVar::Kompas => {
-                ctx.add_points(KOMPAS_USAGE);
-                ctx.glade.griever.kompas()
-            },
-            ExpressionVar::KleurOog => {
-                ctx.add_points(KLEUROOG_USAGE);
-                ctx.glade.color_eye()
-            },
-            ExpressionVar::ZwOog => {
-                ctx.add_points(ZWOOG_USAGE);
-                ctx.glade.bw_eye()
-            },
-        };
-
-        let right = match &self.right {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Int(var) => *var,
-            ExpressionVar::Expression(deeper) => deeper.calc(ctx),
-            ExpressionVar::Kompas => {
-                ctx.add_points(KOMPAS_USAGE);
-                ctx.glade.griever.kompas()
-            },
-            ExpressionVar::KleurOog => {
-                ctx.add_points(KLEUROOG_USAGE);
-                ctx.glade.color_eye()
-            },
-            ExpressionVar::ZwOog => {
-                ctx.add_points(ZWOOG_USAGE);
-                ctx.glade.bw_eye()
-            },
-        };
+    pub fn calc(&self, ctx: &mut Context) -> ExecResult<bool> {
+        ctx.add_points(ctx.rules.comparison_usage)?;
+        let left = self.left.calc(ctx)?;
+        let right = self.right.calc(ctx)?;
 
         // println!("left: {}, op: {:?}, right: {}", left, &self.comparer, right);
 
-        match &self.comparer {
+        Ok(match &self.comparer {
             Comparer::Equal => left == right,
             Comparer::NotEqual => left != right,
             Comparer::GreaterThan => left > right,
             Comparer::SmallerThan => left < right,
-        }
+            Comparer::GreaterOrEqual => left >= right,
+            Comparer::SmallerOrEqual => left <= right,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Condition {
+    Compare(BoolExpression),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    pub fn calc(&self, ctx: &mut Context) -> ExecResult<bool> {
+        Ok(match self {
+            Self::Compare(expression) => expression.calc(ctx)?,
+            Self::And(left, right) => left.calc(ctx)? && right.calc(ctx)?,
+            Self::Or(left, right) => left.calc(ctx)? || right.calc(ctx)?,
+            Self::Not(inner) => !inner.calc(ctx)?,
+        })
     }
 }
 
@@ -391,209 +797,156 @@ pub struct IntExpression {
 }
 
 impl IntExpression {
-    pub fn parse(text: &str, line: usize, ctx: &Context) -> Self {
-        let c = INT_EXPRESSION.captures(&text).unwrap();
-        let left_str = c.get(1).unwrap().as_str();
-        let right_str = c.get(3).unwrap().as_str();
-
-        let operator = match c.get(2).unwrap().as_str() {
-            "+" => Operator::Plus,
-            "-" => Operator::Minus,
-            "/" => Operator::Divide,
-            "*" => Operator::Product,
-            "%" => Operator::Remainder,
-            _ => panic!("unknown operator"),
-        };
+    pub fn calc(&self, ctx: &mut Context) -> ExecResult<i32> {
+        self.value(ctx, true)
+    }
 
-        // println!(
-        //     "left: {}, op: {:?}, right: {}",
-        //     left_str, &operator, right_str
-        // );
+    fn value(&self, ctx: &mut Context, charge: bool) -> ExecResult<i32> {
+        if charge {
+            ctx.add_points(ctx.rules.operation_usage)?;
+        }
+        let left = self.left.value(ctx, charge)?;
+        let right = self.right.value(ctx, charge)?;
 
-        Self {
-            left: ExpressionVar::parse(left_str, line, ctx),
-            right: ExpressionVar::parse(right_str, line, ctx),
-            line,
-            operator,
-        }
-    }
-
-    pub fn calc(&self, ctx: &mut Context) -> i32 {
-        ctx.add_points(OPERATION_USAGE);
-        let left = match &self.left {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Int(var) => *var,
-            ExpressionVar::Expression(deeper) => deeper.calc(ctx),
-            ExpressionVar::Kompas => {
-                ctx.add_points(KOMPAS_USAGE);
-                ctx.glade.griever.kompas()
-            },
-            ExpressionVar::KleurOog => {
-                ctx.add_points(KLEUROOG_USAGE);
-                ctx.glade.color_eye()
-            },
-            ExpressionVar::ZwOog => {
-                ctx.add_points(ZWOOG_USAGE);
-                ctx.glade.bw_eye()
-            },
-        };
+        // println!("left: {}, op: {:?}, right: {}", left, &self.operator, right);
 
-        let right = match &self.right {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Int(var) => *var,
-            ExpressionVar::Expression(deeper) => deeper.calc(ctx),
-            ExpressionVar::Kompas => {
-                ctx.add_points(KOMPAS_USAGE);
-                ctx.glade.griever.kompas()
-            },
-            ExpressionVar::KleurOog => {
-                ctx.add_points(KLEUROOG_USAGE);
-                ctx.glade.color_eye()
-            },
-            ExpressionVar::ZwOog => {
-                ctx.add_points(ZWOOG_USAGE);
-                ctx.glade.bw_eye()
-            },
+        let checked = match &self.operator {
+            Operator::Plus => left.checked_add(right),
+            Operator::Minus => left.checked_sub(right),
+            Operator::Product => left.checked_mul(right),
+            Operator::Divide => left.checked_div(right),
+            Operator::Remainder => left.checked_rem(right),
         };
+        if let Some(result) = checked {
+            return Ok(result);
+        }
 
-        // println!("left: {}, op: {:?}, right: {}", left, &self.operator, right);
-
-        match &self.operator {
-            Operator::Plus => left + right,
-            Operator::Minus => left - right,
-            Operator::Product => left * right,
-            Operator::Divide => left / right,
-            Operator::Remainder => left % right,
+        match ctx.arithmetic {
+            Arithmetic::Wrapping => Ok(match &self.operator {
+                Operator::Plus => left.wrapping_add(right),
+                Operator::Minus => left.wrapping_sub(right),
+                Operator::Product => left.wrapping_mul(right),
+                Operator::Divide | Operator::Remainder if right == 0 => 0,
+                Operator::Divide => left.wrapping_div(right),
+                Operator::Remainder => left.wrapping_rem(right),
+            }),
+            Arithmetic::Checked if right == 0 => Err(InterpretError::runtime(
+                ctx.location(),
+                format!("division by zero in {left} {} 0", self.operator.symbol()),
+            )
+            .into()),
+            Arithmetic::Checked => Err(InterpretError::runtime(
+                ctx.location(),
+                format!(
+                    "the result of {left} {} {right} is too large",
+                    self.operator.symbol()
+                ),
+            )
+            .into()),
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Zolang {
-    pub expression: BoolExpression,
+    pub expression: Condition,
     pub block: CodeBlock,
     pub line: usize,
+    pub column: usize,
 }
 
-impl Zolang {
-    pub fn parse(text: &str, line: usize, ctx: &mut Context) -> LangObject {
-        ctx.add_points(ZOLANG_SOFTWARE);
-        let c = ZOLANG_ID.captures(&text).unwrap_or_else(|| {
-            panic!(
-                "the zolang starting at line {} has a mistake in the syntax",
-                line
-            )
-        });
-        let expr_str = c.get(1).unwrap().as_str().to_owned();
-
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut bracket_open = 0;
-        let mut codeblock: Vec<&str> = Vec::new();
-
-        for (i, line) in lines.iter().enumerate() {
-            if i == 0 {
-                bracket_open += 1;
-                continue;
-            }
+/// Everything the condition and body of a `zolang` depend on. The time only
+/// matters while a bomb is counting down, so when this is the same at the
+/// start of two iterations the loop never ends.
+#[derive(PartialEq)]
+struct LoopState {
+    x: usize,
+    y: usize,
+    direction: i32,
+    targets_reached: i32,
+    seconds: Option<i32>,
+    variables: Vec<(String, Option<i32>)>,
+}
 
-            if line.contains('}') {
-                bracket_open -= 1;
-                if bracket_open == 0 {
-                    break;
+impl Zolang {
+    pub fn run_loop(&self, ctx: &mut Context) -> ExecResult<()> {
+        // Brent's cycle detection, only the state at the last power of two
+        // iterations is kept. A random turner can end any cycle.
+        let detect = !ctx.glade.has_random_turner();
+        let mut saved = None;
+        let (mut power, mut length) = (1, 0);
+
+        ctx.set_position(self.line, self.column, "zolang");
+        while self.expression.calc(ctx)? {
+            if detect {
+                let state = ctx.loop_state();
+                if saved.as_ref() == Some(&state) {
+                    return Err(Interrupt::Halt(RunOutcome::InfiniteLoop {
+                        line: self.line,
+                        points: ctx.points,
+                        budget: ctx.rules.budget,
+                    }));
+                }
+                length += 1;
+                if length == power {
+                    saved = Some(state);
+                    power *= 2;
+                    length = 0;
                 }
             }
-            if line.contains('{') {
-                bracket_open += 1;
+            match self.block.execute(ctx) {
+                Err(Interrupt::Break) => break,
+                res => res?,
             }
-
-            codeblock.push(line)
+            ctx.set_position(self.line, self.column, "zolang");
+            ctx.tick()?;
         }
-
-        LangObject::Zolang(Self {
-            expression: BoolExpression::parse(&expr_str, line, ctx),
-            block: CodeBlock::parse(codeblock.join("\n"), line, ctx),
-            line,
-        })
+        Ok(())
     }
+}
 
-    pub fn run_loop(&self, ctx: &mut Context) {
-        while self.expression.calc(ctx) {
-            self.block.execute(ctx)
+#[derive(Clone, Debug)]
+pub struct Herhaal {
+    pub count: ExpressionVar,
+    pub block: CodeBlock,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Herhaal {
+    pub fn run_loop(&self, ctx: &mut Context) -> ExecResult<()> {
+        let count = self.count.calc(ctx)?;
+
+        for _ in 0..count {
+            ctx.set_position(self.line, self.column, "herhaal");
+            ctx.tick()?;
+            ctx.add_points(ctx.rules.herhaal_usage)?;
+            match self.block.execute(ctx) {
+                Err(Interrupt::Break) => break,
+                res => res?,
+            }
         }
+        Ok(())
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Als {
-    pub expression: BoolExpression,
+    pub expression: Condition,
     pub if_block: CodeBlock,
     pub else_block: Option<CodeBlock>,
     pub line: usize,
+    pub column: usize,
 }
 
 impl Als {
-    pub fn parse(text: &str, line: usize, ctx: &mut Context) -> LangObject {
-        ctx.add_points(ALS_SOFTWARE);
-        let mut bracket_open = 0;
-        let mut anders = false;
-
-        let c = ALS_ID.captures(&text).unwrap_or_else(|| {
-            panic!(
-                "the als starting at line {} has a mistake in the syntax",
-                line
-            )
-        });
-        let expr_str = c.get(1).unwrap().as_str().to_owned();
-
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut if_codeblock_str: Vec<&str> = Vec::new();
-        let mut else_block_str: Vec<&str> = Vec::new();
-
-        for (i, line) in lines.iter().enumerate() {
-            if i == 0 {
-                bracket_open += 1;
-                continue;
-            }
-            if line.trim() == "} anders {" && bracket_open == 1 {
-                anders = true;
-            } else {
-                if line.contains('}') {
-                    bracket_open -= 1;
-                    if bracket_open == 0 {
-                        break;
-                    }
-                }
-                if line.contains('{') {
-                    bracket_open += 1;
-                }
-
-                if anders {
-                    else_block_str.push(line)
-                } else {
-                    if_codeblock_str.push(line)
-                }
-            }
-        }
-
-        let else_block = if else_block_str.is_empty() {
-            None
-        } else {
-            Some(CodeBlock::parse(else_block_str.join("\n"), line + 1, ctx))
-        };
-
-        LangObject::Als(Self {
-            expression: BoolExpression::parse(&expr_str, line, ctx),
-            if_block: CodeBlock::parse(if_codeblock_str.join("\n"), line + 1, ctx),
-            else_block,
-            line,
-        })
-    }
-
-    pub fn run_if(&self, ctx: &mut Context) {
-        if self.expression.calc(ctx) {
+    pub fn run_if(&self, ctx: &mut Context) -> ExecResult<()> {
+        if self.expression.calc(ctx)? {
             self.if_block.execute(ctx)
-        } else if self.else_block.is_some() {
-            self.else_block.as_ref().unwrap().execute(ctx)
+        } else if let Some(else_block) = &self.else_block {
+            else_block.execute(ctx)
+        } else {
+            Ok(())
         }
     }
 }
@@ -603,86 +956,213 @@ pub struct Assignment {
     pub var: String,
     pub expression: ExpressionVar,
     pub line: usize,
+    pub column: usize,
 }
 
 impl Assignment {
-    pub fn parse(text: &str, line: usize, ctx: &mut Context) -> LangObject {
-        ctx.add_points(ASSIGNMENT_SOFTWARE);
-        let c = ASSIGNMENT.captures(&text).unwrap_or_else(|| {
-            panic!(
-                "there is an error in the syntax of the assignment at line {}",
-                line
-            )
-        });
-        let name = c.get(1).unwrap().as_str().to_owned();
-        let to_assign = c.get(2).unwrap().as_str();
-
-        let expression = ExpressionVar::parse(to_assign, line, ctx);
+    pub fn calc(&self, ctx: &mut Context) -> ExecResult<()> {
+        ctx.add_points(ctx.rules.assignment_usage)?;
+        let value = self.expression.calc(ctx)?;
 
-        LangObject::Assignment(Self {
-            var: name,
-            line,
-            expression,
-        })
+        ctx.variables.insert(self.var.clone(), Some(value));
+        Ok(())
     }
+}
 
-    pub fn calc(&self, ctx: &mut Context) {
-        ctx.add_points(ASSIGNMENT_USAGE);
-        let value = match &self.expression {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Expression(exp) => exp.calc(ctx),
-            ExpressionVar::Int(i) => *i,
-            ExpressionVar::Kompas => {
-                ctx.add_points(KOMPAS_USAGE);
-                ctx.glade.griever.kompas()
-            },
-            _ => panic!(
-                "these values are not currently supported for an assignment, assignment at line: {}",
-                self.line
-            ),
-        };
+#[derive(Clone, Debug)]
+pub struct Print {
+    pub expression: ExpressionVar,
+    pub line: usize,
+    pub column: usize,
+}
 
-        if ctx.variables.get(&self.var).is_some() {
-            ctx.variables.insert(self.var.clone(), Some(value));
-            return;
-        }
+impl Print {
+    pub fn print(&self, ctx: &mut Context) -> ExecResult<()> {
+        let value = self.expression.value(ctx, ctx.charge_print)?;
 
-        panic!(
-            "tried to assign to a non-existant variable at line {}",
-            self.line
-        )
+        println!("at line {} print: {}", self.line, value);
+        Ok(())
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct Print {
-    pub expression: ExpressionVar,
+pub struct Procedure {
+    pub name: String,
+    pub block: CodeBlock,
     pub line: usize,
+    pub column: usize,
 }
 
-impl Print {
-    pub fn parse(text: &str, line: usize, ctx: &Context) -> LangObject {
-        let c = PRINT.captures(&text).unwrap();
-        let to_print = c.get(1).unwrap().as_str();
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
 
-        let expression = ExpressionVar::parse(to_print, line, ctx);
+impl Call {
+    pub fn call(&self, ctx: &mut Context) -> ExecResult<()> {
+        let procedure = ctx.procedures.get(&self.name).cloned().ok_or_else(|| {
+            InterpretError::runtime(
+                ctx.location(),
+                format!("procedure {} is not defined", self.name),
+            )
+        })?;
+        if ctx.call_depth >= ctx.max_call_depth {
+            return Err(InterpretError::runtime(
+                ctx.location(),
+                format!(
+                    "procedure {} went more than {} calls deep, is there a recursion without an end?",
+                    self.name, ctx.max_call_depth
+                ),
+            )
+            .into());
+        }
 
-        LangObject::Print(Self {
-            expression,
-            line,
-        })
+        ctx.call_depth += 1;
+        let res = procedure.block.execute(ctx);
+        ctx.call_depth -= 1;
+        res
     }
+}
 
-    pub fn print(&self, ctx: &mut Context) {
-        let value = match &self.expression {
-            ExpressionVar::Variable(inner_var) => ctx.get_var(&inner_var),
-            ExpressionVar::Expression(exp) => exp.calc(ctx),
-            ExpressionVar::Int(i) => *i,
-            ExpressionVar::Kompas => ctx.glade.griever.kompas(),
-            ExpressionVar::KleurOog => ctx.glade.color_eye(),
-            ExpressionVar::ZwOog => ctx.glade.bw_eye(),
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn run(code: &str, glade: &str) -> RunOutcome {
+        let glade = Glade::parse_str("glade.csv", glade).expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        ctx.parse().expect("the code is valid");
+        ctx.execute().expect("the code runs")
+    }
+
+    #[test]
+    fn obstacle_loop() {
+        let code = "gebruik kompas\nzolang kompas == 1 {\n    stapVooruit\n}";
+        let outcome = run(code, "s1;q;w");
+        assert!(
+            matches!(
+                outcome,
+                RunOutcome::InfiniteLoop {
+                    line: 2,
+                    ..
+                }
+            ),
+            "{:?}",
+            outcome
+        );
+    }
+
+    #[test]
+    fn moving_loop() {
+        let code = "zolang 1 == 1 {\n    stapVooruit\n    draaiRechts\n    draaiRechts\n}";
+        let outcome = run(code, "s1;w;w;w\nw;w;w;t1");
+        assert!(
+            matches!(
+                outcome,
+                RunOutcome::InfiniteLoop {
+                    line: 1,
+                    ..
+                }
+            ),
+            "{:?}",
+            outcome
+        );
+    }
+
+    #[test]
+    fn ending_loop() {
+        let code = "gebruik a\na = 0\nzolang a < 5 {\n    a = a + 1\n}\nstapVooruit\nstapVooruit";
+        assert!(run(code, "s1;w;t1").is_success());
+    }
+
+    #[test]
+    fn armed_bomb() {
+        // the griever walks over the bomb and back until it explodes
+        let code = "zolang 1 == 1 {\n    stapVooruit\n    stapAchteruit\n}";
+        let outcome = run(code, "s1;x2;w;t1");
+        assert!(
+            matches!(
+                outcome,
+                RunOutcome::Exploded {
+                    x: 1,
+                    y: 0,
+                    ..
+                }
+            ),
+            "{:?}",
+            outcome
+        );
+    }
+
+    #[test]
+    fn step_usage() {
+        let glade = Glade::parse_str("glade.csv", "s1;w;w;t1").expect("the test glade is valid");
+        let mut ctx = Context::new("stapVooruit\nstapAchteruit\nstapVooruit", glade);
+        ctx.rules.stepforward_usage = 2;
+        ctx.rules.stepbackwards_usage = 3;
+        ctx.parse().expect("the code is valid");
+        let parsed = ctx.points;
+        assert_eq!(ctx.estimate().once, 7);
+        ctx.execute().expect("the code runs");
+        assert_eq!(ctx.points - parsed, 7);
+    }
+
+    #[test]
+    fn exploded_bomb() {
+        // the bomb's time has passed before the loop starts, so it doesn't keep the
+        // loop from being detected
+        let code = "stapVooruit\nstapVooruit\nzolang 1 == 1 {\n    stapVooruit\n}";
+        let outcome = run(code, "s1;x2;w;q;t1");
+        assert!(
+            matches!(
+                outcome,
+                RunOutcome::InfiniteLoop {
+                    line: 3,
+                    ..
+                }
+            ),
+            "{:?}",
+            outcome
+        );
+    }
 
-        println!("at line {} print: {}", self.line, value)
+    #[test]
+    fn callbacks() {
+        let code = "stapVooruit\ndraaiLinks\ndraaiRechts\nstapVooruit\nstapVooruit";
+        let glade =
+            Glade::parse_str("glade.csv", "s1;t2;q\nw;w;t1").expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        let moves = Rc::new(RefCell::new(Vec::new()));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&moves);
+        ctx.on_move = Some(Rc::new(move |ctx: &Context| {
+            let griever = &ctx.glade.griever;
+            sink.borrow_mut()
+                .push((griever.x, griever.y, griever.kompas()));
+        }));
+        let sink = Rc::clone(&events);
+        ctx.on_event = Some(Rc::new(move |_: &Context, event: &Event| {
+            sink.borrow_mut().push(event.to_string());
+        }));
+        ctx.parse().expect("the code is valid");
+        ctx.execute().expect("the code runs");
+
+        assert_eq!(
+            *moves.borrow(),
+            [(1, 0, 1), (1, 0, 0), (1, 0, 1), (1, 0, 1), (1, 0, 1)]
+        );
+        assert_eq!(
+            *events.borrow(),
+            [
+                "passed target 2",
+                "passed target 2",
+                "passed target 2",
+                "WARNING: collided against obstacle! location: 2, 1, direction: East",
+                "WARNING: collided against obstacle! location: 2, 1, direction: East",
+            ]
+        );
     }
 }
@@ -0,0 +1,203 @@
+use super::{error::InterpretError, map::Direction};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RunOutcome {
+    Success {
+        points: i32,
+        budget: i32,
+    },
+    Failed {
+        targets_reached: i32,
+        points: i32,
+        budget: i32,
+    },
+    Exploded {
+        x: usize,
+        y: usize,
+        points: i32,
+    },
+    OverBudget {
+        points: i32,
+    },
+    CollisionLimit {
+        collisions: u32,
+        points: i32,
+        budget: i32,
+    },
+    Stopped {
+        line: usize,
+        targets_reached: i32,
+        points: i32,
+        budget: i32,
+    },
+    InstructionLimit {
+        instructions: u64,
+        points: i32,
+        budget: i32,
+    },
+    InfiniteLoop {
+        line: usize,
+        points: i32,
+        budget: i32,
+    },
+}
+
+impl RunOutcome {
+    pub fn points(&self) -> i32 {
+        match self {
+            Self::Success {
+                points, ..
+            }
+            | Self::Failed {
+                points, ..
+            }
+            | Self::Exploded {
+                points, ..
+            }
+            | Self::OverBudget {
+                points,
+            }
+            | Self::CollisionLimit {
+                points, ..
+            }
+            | Self::Stopped {
+                points, ..
+            }
+            | Self::InstructionLimit {
+                points, ..
+            }
+            | Self::InfiniteLoop {
+                points, ..
+            } => *points,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+impl fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Success {
+                points,
+                budget,
+            } => write!(f, "SUCCESS!\ncosts: {}", budget - points),
+            Self::Failed {
+                targets_reached,
+                points,
+                budget,
+            } => write!(
+                f,
+                "FAILED\ntargets reached: {}\npoints left: {}",
+                targets_reached,
+                budget - points
+            ),
+            Self::Exploded {
+                x,
+                y,
+                ..
+            } => write!(f, "BOOM!\nYou're dead at {}, {}", x + 1, y + 1),
+            Self::OverBudget {
+                points,
+            } => write!(
+                f,
+                "Used up too much of your money! your total expenses are: {points}"
+            ),
+            Self::CollisionLimit {
+                collisions,
+                points,
+                budget,
+            } => write!(
+                f,
+                "STOPPED after {} collisions\npoints left: {}",
+                collisions,
+                budget - points
+            ),
+            Self::Stopped {
+                line,
+                targets_reached,
+                points,
+                budget,
+            } => write!(
+                f,
+                "STOPPED at line {}\ntargets reached: {}\npoints left: {}",
+                line,
+                targets_reached,
+                budget - points
+            ),
+            Self::InstructionLimit {
+                instructions,
+                points,
+                budget,
+            } => write!(
+                f,
+                "STOPPED after {} instructions\npoints left: {}",
+                instructions,
+                budget - points
+            ),
+            Self::InfiniteLoop {
+                line,
+                points,
+                budget,
+            } => write!(
+                f,
+                "STOPPED because the zolang at line {} never ends\npoints left: {}",
+                line,
+                budget - points
+            ),
+        }
+    }
+}
+
+/// Something that happens to the griever during a run, which the original
+/// tool prints as it goes.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// The griever passed the target with this number, counting from 0.
+    Target(i32),
+    /// The griever collided with an obstacle and stays at this position.
+    Collision {
+        x: usize,
+        y: usize,
+        direction: Direction,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Target(n) => write!(f, "passed target {}", n + 1),
+            Self::Collision {
+                x,
+                y,
+                direction,
+            } => write!(
+                f,
+                "WARNING: collided against obstacle! location: {}, {}, direction: {:?}",
+                x + 1,
+                y + 1,
+                direction
+            ),
+        }
+    }
+}
+
+/// Stops the execution of a program, either because of an error or because
+/// the run has come to an end. `Break` only travels up to the innermost loop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Interrupt {
+    Error(InterpretError),
+    Halt(RunOutcome),
+    Break,
+}
+
+impl From<InterpretError> for Interrupt {
+    fn from(e: InterpretError) -> Self {
+        Self::Error(e)
+    }
+}
+
+pub type ExecResult<T> = Result<T, Interrupt>;
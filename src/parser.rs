@@ -0,0 +1,929 @@
+use super::{
+    error::{InterpretError, InterpretResult},
+    lexer::{Token, TokenKind, KEYWORDS},
+    objects::*,
+    profile::CostKind,
+};
+use std::rc::Rc;
+
+const EXTENDED_STATEMENTS: &[&str] = &["functie", "roep", "herhaal"];
+
+pub struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+    loops: usize,
+    ctx: &'a mut Context,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(tokens: Vec<Token>, ctx: &'a mut Context) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            depth: 0,
+            loops: 0,
+            ctx,
+        }
+    }
+
+    pub fn parse(mut self) -> InterpretResult<CodeBlock> {
+        let objects = self.parse_statements()?;
+        if self.peek().kind != TokenKind::Eof {
+            return Err(self.error("a closing } without an opening {"));
+        }
+
+        Ok(CodeBlock {
+            objects,
+            line: 0,
+        })
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        &self.tokens[(self.pos + n).min(self.tokens.len() - 1)]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: &str) -> InterpretError {
+        let token = self.peek();
+        InterpretError::syntax(self.ctx.at(token.line, token.column), message)
+    }
+
+    fn expected(&self, what: &str) -> InterpretError {
+        self.error(&format!("expected {}, found {}", what, self.peek().kind))
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> InterpretResult<Token> {
+        if &self.peek().kind == kind {
+            Ok(self.advance())
+        } else {
+            Err(self.expected(&kind.to_string()))
+        }
+    }
+
+    fn parse_statements(&mut self) -> InterpretResult<Vec<LangObject>> {
+        let mut objects = Vec::new();
+
+        loop {
+            while self.peek().kind == TokenKind::Newline {
+                self.advance();
+            }
+            if matches!(self.peek().kind, TokenKind::Eof | TokenKind::CloseBrace) {
+                return Ok(objects);
+            }
+
+            if let Some(object) = self.parse_statement()? {
+                objects.push(object);
+            }
+
+            match self.peek().kind {
+                TokenKind::Newline => {
+                    self.advance();
+                },
+                TokenKind::Eof => {},
+                TokenKind::CloseBrace => {
+                    return Err(self.error("a closing } has to be on a newline"))
+                },
+                _ => return Err(self.expected("end of line")),
+            }
+        }
+    }
+
+    fn parse_statement(&mut self) -> InterpretResult<Option<LangObject>> {
+        let token = self.peek().clone();
+        let (line, column) = (token.line, token.column);
+
+        let word = match &token.kind {
+            TokenKind::Word(word) => word.as_str(),
+            _ => return Err(self.expected("a statement")),
+        };
+        let statement = KEYWORDS
+            .iter()
+            .find(|k| **k == word)
+            .map_or("assignment", |k| k);
+        self.ctx.set_position(line, column, statement);
+        self.check_dialect(word)?;
+
+        let object = match word {
+            "gebruik" => {
+                self.advance();
+                self.advance();
+                return Ok(None);
+            },
+            "stapVooruit" | "stapAchteruit" | "draaiLinks" | "draaiRechts" => {
+                self.advance();
+                self.ctx
+                    .charge(CostKind::Software, self.ctx.rules.action_software);
+                action(word, line, column)
+            },
+            "zolang" => {
+                self.advance();
+                self.ctx
+                    .charge(CostKind::Software, self.ctx.rules.zolang_software);
+                LangObject::Zolang(Zolang {
+                    expression: self.parse_condition()?,
+                    block: self.parse_loop_block()?,
+                    line,
+                    column,
+                })
+            },
+            "breek" => {
+                if self.loops == 0 {
+                    return Err(
+                        self.error("`breek` can only be used inside a zolang or herhaal loop")
+                    );
+                }
+                self.advance();
+                self.ctx
+                    .charge(CostKind::Software, self.ctx.rules.breek_software);
+                LangObject::Break {
+                    line,
+                    column,
+                }
+            },
+            "stop" => {
+                self.advance();
+                self.ctx
+                    .charge(CostKind::Software, self.ctx.rules.stop_software);
+                LangObject::Stop {
+                    line,
+                    column,
+                }
+            },
+            "herhaal" => {
+                self.advance();
+                LangObject::Herhaal(self.parse_herhaal(line, column)?)
+            },
+            "als" => {
+                self.advance();
+                LangObject::Als(self.parse_als(line, column)?)
+            },
+            "anders" => {
+                return Err(
+                    self.error("`anders` has to be on the same line as the closing } of the als")
+                )
+            },
+            "functie" => {
+                self.advance();
+                self.parse_procedure(line, column)?;
+                return Ok(None);
+            },
+            "roep" => {
+                self.advance();
+                LangObject::Call(self.parse_call(line, column)?)
+            },
+            "print" => {
+                self.advance();
+                LangObject::Print(Print {
+                    expression: self.parse_expression()?,
+                    line,
+                    column,
+                })
+            },
+            _ if self.peek_nth(1).kind == TokenKind::Assign => {
+                LangObject::Assignment(self.parse_assignment(line, column)?)
+            },
+            _ => return Err(self.expected("a statement")),
+        };
+
+        Ok(Some(object))
+    }
+
+    /// Rejects statements that the current dialect doesn't have.
+    fn check_dialect(&self, word: &str) -> InterpretResult<()> {
+        if EXTENDED_STATEMENTS.contains(&word) && self.ctx.dialect != Dialect::Extended {
+            return Err(self.error(&format!(
+                "`{word}` is only available in the extended dialect"
+            )));
+        }
+        if matches!(word, "breek" | "stop") && self.ctx.dialect == Dialect::Taal20 {
+            return Err(self.error(&format!("`{word}` is not available in taal 20 mode")));
+        }
+        Ok(())
+    }
+
+    fn parse_assignment(&mut self, line: usize, column: usize) -> InterpretResult<Assignment> {
+        let TokenKind::Word(var) = self.advance().kind else {
+            unreachable!("assignments start with a word")
+        };
+        if !self.ctx.variables.contains_key(&var) {
+            return Err(InterpretError::semantic(
+                self.ctx.at(line, column),
+                format!("tried to assign to the non-existent variable {var}"),
+            ));
+        }
+        self.advance();
+        self.ctx
+            .charge(CostKind::Software, self.ctx.rules.assignment_software);
+
+        Ok(Assignment {
+            var,
+            expression: self.parse_expression()?,
+            line,
+            column,
+        })
+    }
+
+    fn parse_herhaal(&mut self, line: usize, column: usize) -> InterpretResult<Herhaal> {
+        self.ctx
+            .charge(CostKind::Software, self.ctx.rules.herhaal_software);
+        let count = self.parse_expression()?;
+        if self.peek().kind != TokenKind::Word(String::from("keer")) {
+            return Err(self.expected("`keer`"));
+        }
+        self.advance();
+
+        Ok(Herhaal {
+            count,
+            block: self.parse_loop_block()?,
+            line,
+            column,
+        })
+    }
+
+    fn parse_als(&mut self, line: usize, column: usize) -> InterpretResult<Als> {
+        self.ctx
+            .charge(CostKind::Software, self.ctx.rules.als_software);
+        let expression = self.parse_condition()?;
+        let if_block = self.parse_block()?;
+
+        let else_block = if self.peek().kind == TokenKind::Word(String::from("anders")) {
+            self.advance();
+            if self.peek().kind == TokenKind::Word(String::from("als")) {
+                if self.ctx.dialect == Dialect::Taal20 {
+                    return Err(self.error("`anders als` is not available in taal 20 mode"));
+                }
+
+                // an `anders als` is an als nested in the anders block, so it is charged and
+                // evaluated exactly like one
+                let token = self.advance();
+                self.ctx.set_position(token.line, token.column, "als");
+                let nested = self.parse_als(token.line, token.column)?;
+
+                Some(CodeBlock {
+                    objects: vec![LangObject::Als(nested)],
+                    line: token.line,
+                })
+            } else {
+                Some(self.parse_block()?)
+            }
+        } else {
+            None
+        };
+
+        Ok(Als {
+            expression,
+            if_block,
+            else_block,
+            line,
+            column,
+        })
+    }
+
+    fn parse_procedure(&mut self, line: usize, column: usize) -> InterpretResult<()> {
+        if self.depth > 0 {
+            return Err(InterpretError::syntax(
+                self.ctx.at(line, column),
+                "procedures can only be declared at the top level",
+            ));
+        }
+        let TokenKind::Word(name) = self.advance().kind else {
+            unreachable!("procedure names are checked when registering them")
+        };
+        self.ctx
+            .charge(CostKind::Software, self.ctx.rules.functie_software);
+
+        let procedure = Procedure {
+            name: name.clone(),
+            block: self.parse_block()?,
+            line,
+            column,
+        };
+        self.ctx.procedures.insert(name, Rc::new(procedure));
+        Ok(())
+    }
+
+    fn parse_call(&mut self, line: usize, column: usize) -> InterpretResult<Call> {
+        let token = self.peek().clone();
+        let TokenKind::Word(name) = token.kind else {
+            return Err(self.expected("a procedure name"));
+        };
+        if self.ctx.symbols.get(&name).map(|s| &s.kind) != Some(&SymbolKind::Procedure) {
+            return Err(InterpretError::semantic(
+                self.ctx.at(token.line, token.column),
+                format!("procedure {name} is not defined"),
+            ));
+        }
+        self.advance();
+        self.ctx
+            .charge(CostKind::Software, self.ctx.rules.roep_software);
+
+        Ok(Call {
+            name,
+            line,
+            column,
+        })
+    }
+
+    fn parse_block(&mut self) -> InterpretResult<CodeBlock> {
+        let open = self.expect(&TokenKind::OpenBrace)?;
+        if self.peek().kind != TokenKind::Newline {
+            return Err(self.expected("end of line after `{`"));
+        }
+
+        self.depth += 1;
+        let objects = self.parse_statements()?;
+        self.depth -= 1;
+        if self.peek().kind != TokenKind::CloseBrace {
+            return Err(self.expected(&format!(
+                "`}}` to close the block opened at {}:{}",
+                open.line, open.column
+            )));
+        }
+        self.advance();
+
+        Ok(CodeBlock {
+            objects,
+            line: open.line,
+        })
+    }
+
+    fn parse_loop_block(&mut self) -> InterpretResult<CodeBlock> {
+        self.loops += 1;
+        let block = self.parse_block();
+        self.loops -= 1;
+        block
+    }
+
+    fn parse_condition(&mut self) -> InterpretResult<Condition> {
+        let mut left = self.parse_and()?;
+        while self.logical("of")? {
+            left = Condition::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> InterpretResult<Condition> {
+        let mut left = self.parse_not()?;
+        while self.logical("en")? {
+            left = Condition::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> InterpretResult<Condition> {
+        if self.logical("niet")? {
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+
+        let start = self.pos;
+        match self.parse_comparison() {
+            Ok(comparison) => Ok(Condition::Compare(comparison)),
+            // the parenthesis did not belong to an arithmetic expression, so try it as a
+            // grouped condition instead
+            Err(_)
+                if self.ctx.dialect == Dialect::Extended
+                    && self.tokens[start].kind == TokenKind::LeftParen =>
+            {
+                self.pos = start + 1;
+                let condition = self.parse_condition()?;
+                self.expect(&TokenKind::RightParen)?;
+                Ok(condition)
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn logical(&mut self, word: &str) -> InterpretResult<bool> {
+        if self.peek().kind != TokenKind::Word(word.to_owned()) {
+            return Ok(false);
+        }
+        if self.ctx.dialect < Dialect::Extended {
+            return Err(self.error(&format!(
+                "`{word}` is only available in the extended dialect"
+            )));
+        }
+        self.advance();
+        Ok(true)
+    }
+
+    fn parse_comparison(&mut self) -> InterpretResult<BoolExpression> {
+        let line = self.peek().line;
+        let left = self.parse_expression()?;
+
+        let comparer = match self.peek().kind {
+            TokenKind::Equal => Comparer::Equal,
+            TokenKind::NotEqual => Comparer::NotEqual,
+            TokenKind::GreaterThan => Comparer::GreaterThan,
+            TokenKind::SmallerThan => Comparer::SmallerThan,
+            TokenKind::GreaterOrEqual | TokenKind::SmallerOrEqual
+                if self.ctx.dialect == Dialect::Taal20 =>
+            {
+                return Err(self.error(&format!(
+                    "{} is not available in taal 20 mode, taal 20 only knows ==, !=, > and <",
+                    self.peek().kind
+                )))
+            },
+            TokenKind::GreaterOrEqual => Comparer::GreaterOrEqual,
+            TokenKind::SmallerOrEqual => Comparer::SmallerOrEqual,
+            _ => return Err(self.expected("a comparer (==, !=, >, <, >= or <=)")),
+        };
+        self.advance();
+
+        Ok(BoolExpression {
+            left,
+            comparer,
+            right: self.parse_expression()?,
+            line,
+        })
+    }
+
+    fn parse_expression(&mut self) -> InterpretResult<ExpressionVar> {
+        if self.ctx.dialect == Dialect::Taal20 {
+            self.parse_taal20_expression()
+        } else {
+            self.parse_binary(0)
+        }
+    }
+
+    fn peek_operator(&self) -> Option<(Operator, u8)> {
+        match self.peek().kind {
+            TokenKind::Plus => Some((Operator::Plus, 1)),
+            TokenKind::Minus => Some((Operator::Minus, 1)),
+            TokenKind::Star => Some((Operator::Product, 2)),
+            TokenKind::Slash => Some((Operator::Divide, 2)),
+            TokenKind::Percent => Some((Operator::Remainder, 2)),
+            _ => None,
+        }
+    }
+
+    /// Taal 20 has no operator precedence, operations are simply evaluated
+    /// from left to right.
+    fn parse_taal20_expression(&mut self) -> InterpretResult<ExpressionVar> {
+        let line = self.peek().line;
+        let mut left = self.parse_atom()?;
+
+        while let Some((operator, _)) = self.peek_operator() {
+            self.advance();
+            left = operation(left, operator, self.parse_atom()?, line);
+        }
+
+        Ok(left)
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8) -> InterpretResult<ExpressionVar> {
+        let line = self.peek().line;
+        let mut left = self.parse_unary()?;
+
+        while let Some((operator, precedence)) = self.peek_operator() {
+            if precedence < min_precedence {
+                break;
+            }
+            self.advance();
+            left = operation(left, operator, self.parse_binary(precedence + 1)?, line);
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> InterpretResult<ExpressionVar> {
+        let line = self.peek().line;
+
+        match self.peek().kind {
+            TokenKind::Minus => {
+                self.advance();
+                Ok(match self.parse_unary()? {
+                    ExpressionVar::Int(i) => ExpressionVar::Int(-i),
+                    value => operation(ExpressionVar::Int(0), Operator::Minus, value, line),
+                })
+            },
+            TokenKind::LeftParen => {
+                self.advance();
+                let value = self.parse_binary(0)?;
+                self.expect(&TokenKind::RightParen)?;
+                Ok(value)
+            },
+            _ => self.parse_atom(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> InterpretResult<ExpressionVar> {
+        let token = self.peek().clone();
+        let location = self.ctx.at(token.line, token.column);
+
+        let value = match &token.kind {
+            TokenKind::Int(i) => ExpressionVar::Int(*i),
+            TokenKind::Minus => {
+                self.advance();
+                if let TokenKind::Int(i) = self.peek().kind {
+                    ExpressionVar::Int(-i)
+                } else {
+                    return Err(self.expected("a number"));
+                }
+            },
+            TokenKind::Word(word) => {
+                let hardware = match word.as_str() {
+                    "kompas" => Some((Hardware::Kompas, ExpressionVar::Kompas)),
+                    "zwOog" => Some((Hardware::ZwOog, ExpressionVar::ZwOog)),
+                    "kleurOog" => Some((Hardware::KleurOog, ExpressionVar::KleurOog)),
+                    _ => None,
+                };
+
+                if let Some((h, value)) = hardware {
+                    if !self.ctx.useable.contains(&h) {
+                        return Err(InterpretError::semantic(
+                            location,
+                            format!("{word} is used without `gebruik {word}`"),
+                        ));
+                    }
+                    value
+                } else if self.ctx.variables.contains_key(word) {
+                    ExpressionVar::Variable(word.clone())
+                } else if self.ctx.is_variable_name(word) {
+                    return Err(InterpretError::semantic(
+                        location,
+                        format!("variable {word} is not defined"),
+                    ));
+                } else {
+                    return Err(self.expected("a value"));
+                }
+            },
+            TokenKind::LeftParen => {
+                return Err(self.error("parentheses are not available in taal 20 mode"))
+            },
+            _ => return Err(self.expected("a value")),
+        };
+        self.advance();
+
+        Ok(value)
+    }
+}
+
+fn operation(
+    left: ExpressionVar,
+    operator: Operator,
+    right: ExpressionVar,
+    line: usize,
+) -> ExpressionVar {
+    ExpressionVar::Expression(Box::new(IntExpression {
+        left,
+        operator,
+        right,
+        line,
+    }))
+}
+
+fn action(word: &str, line: usize, column: usize) -> LangObject {
+    match word {
+        "stapVooruit" => LangObject::StepForwards {
+            line,
+            column,
+        },
+        "stapAchteruit" => LangObject::StepBackwards {
+            line,
+            column,
+        },
+        "draaiLinks" => LangObject::TurnLeft {
+            line,
+            column,
+        },
+        _ => LangObject::TurnRight {
+            line,
+            column,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Comment, map::Glade, outcome::RunOutcome, rules::Rules};
+
+    fn context(code: &str, dialect: Dialect) -> Context {
+        let glade = Glade::parse_str("glade.csv", "s1;w;w;t1").expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        ctx.dialect = dialect;
+        if dialect == Dialect::Taal20 {
+            ctx.arithmetic = Arithmetic::Wrapping;
+        }
+        ctx
+    }
+
+    fn parse(code: &str, dialect: Dialect) -> Context {
+        let mut ctx = context(code, dialect);
+        ctx.parse().expect("the code is valid");
+        ctx
+    }
+
+    fn error(code: &str, dialect: Dialect) -> InterpretError {
+        context(code, dialect)
+            .parse()
+            .expect_err("the code is invalid")
+    }
+
+    /// Runs the code and returns the value of `a` at the end.
+    fn run(code: &str, dialect: Dialect) -> InterpretResult<i32> {
+        let mut ctx = parse(&format!("gebruik a\n{code}"), dialect);
+        ctx.execute()?;
+        ctx.get_var("a")
+    }
+
+    /// Runs the code and returns the usage points it spent, on top of the
+    /// points charged while parsing.
+    fn usage(code: &str, dialect: Dialect) -> i32 {
+        let mut ctx = parse(code, dialect);
+        let parsed = ctx.points;
+        ctx.execute().expect("the code runs");
+        ctx.points - parsed
+    }
+
+    fn kinds(block: &CodeBlock) -> Vec<&'static str> {
+        block.objects.iter().map(LangObject::kind).collect()
+    }
+
+    #[test]
+    fn instructions() {
+        let ctx = parse(include_str!("../instructions.txt"), Dialect::Standard);
+        assert_eq!(
+            kinds(&ctx.code),
+            [
+                "stapVooruit",
+                "stapVooruit",
+                "zolang",
+                "print",
+                "draaiLinks",
+                "assignment",
+                "zolang"
+            ]
+        );
+        assert_eq!(ctx.code.objects[2].position(), (8, 1));
+
+        let LangObject::Zolang(zolang) = &ctx.code.objects[6] else {
+            panic!("expected a zolang, found {:?}", ctx.code.objects[6]);
+        };
+        assert_eq!(kinds(&zolang.block), ["print", "assignment", "stapVooruit"]);
+        assert_eq!(zolang.block.line, 18);
+    }
+
+    #[test]
+    fn block_errors() {
+        let e = error(
+            "gebruik a\nzolang a < 4 {\n    stapVooruit\n",
+            Dialect::Standard,
+        );
+        assert_eq!((e.location().line, e.location().column), (4, 1));
+        assert_eq!(
+            e.message(),
+            "expected `}` to close the block opened at 2:14, found end of file"
+        );
+
+        let e = error("stapVooruit\n}", Dialect::Standard);
+        assert_eq!((e.location().line, e.location().column), (2, 1));
+        assert_eq!(e.message(), "a closing } without an opening {");
+
+        let e = error("als 1 == 1 {\n    stapVooruit }", Dialect::Standard);
+        assert_eq!(e.message(), "a closing } has to be on a newline");
+
+        let e = error("als 1 == 1 { stapVooruit\n}", Dialect::Standard);
+        assert_eq!(
+            e.message(),
+            "expected end of line after `{`, found `stapVooruit`"
+        );
+    }
+
+    #[test]
+    fn declared_twice() {
+        let code = "gebruik kompas\ngebruik kompas\ngebruik a\ngebruik a";
+        let ctx = parse(code, Dialect::Taal20);
+        let rules = &ctx.rules;
+        assert_eq!(
+            ctx.points,
+            2 * rules.kompas_hardware + 2 * rules.var_hardware
+        );
+        assert_eq!(ctx.useable, [Hardware::Kompas]);
+
+        let e = error(code, Dialect::Extended);
+        assert_eq!((e.location().line, e.location().column), (2, 9));
+        assert_eq!(e.message(), "`kompas` is already declared at line 1");
+    }
+
+    #[test]
+    fn procedure_names() {
+        let e = error("functie Rondje {\n    stapVooruit\n}", Dialect::Extended);
+        assert_eq!(e.message(), "`Rondje` is not a valid procedure name, procedures start with a lowercase letter followed by letters, digits or underscores");
+
+        let e = error("functie stop {\n    stapVooruit\n}", Dialect::Extended);
+        assert_eq!(
+            e.message(),
+            "`stop` is a keyword and can't be used as a procedure name"
+        );
+    }
+
+    #[test]
+    fn anders() {
+        let code = "als 1 == 2 {\n    a = 1\n} anders {\n    a = 2\n    a = a + 1\n}";
+        let ctx = parse(&format!("gebruik a\n{code}"), Dialect::Standard);
+        let LangObject::Als(als) = &ctx.code.objects[0] else {
+            panic!("expected an als, found {:?}", ctx.code.objects[0]);
+        };
+        assert_eq!(kinds(&als.if_block), ["assignment"]);
+        let else_block = als.else_block.as_ref().expect("the als has an anders");
+        assert_eq!(kinds(else_block), ["assignment", "assignment"]);
+
+        assert_eq!(run(code, Dialect::Standard), Ok(3));
+    }
+
+    #[test]
+    fn anders_als() {
+        let code = |b: i32| {
+            format!(
+                "gebruik b\nb = {b}\nals b == 1 {{\n    a = 10\n}} anders als b == 2 {{\n    a = 20\n}} anders {{\n    a = 30\n}}"
+            )
+        };
+        assert_eq!(run(&code(1), Dialect::Standard), Ok(10));
+        assert_eq!(run(&code(2), Dialect::Standard), Ok(20));
+        assert_eq!(run(&code(3), Dialect::Standard), Ok(30));
+
+        let e = error(&format!("gebruik a\n{}", code(1)), Dialect::Taal20);
+        assert_eq!(e.message(), "`anders als` is not available in taal 20 mode");
+    }
+
+    #[test]
+    fn herhaal() {
+        let code = "gebruik n\nn = 2\na = 0\nherhaal n + 1 keer {\n    a = a + 1\n    n = 0\n}";
+        assert_eq!(run(code, Dialect::Extended), Ok(3));
+
+        let code = "gebruik kompas\na = 0\nherhaal kompas keer {\n    a = a + 1\n}";
+        let mut ctx = parse(&format!("gebruik a\n{code}"), Dialect::Extended);
+        let parsed = ctx.points;
+        ctx.execute().expect("the code runs");
+        assert_eq!(ctx.get_var("a"), Ok(1));
+        let rules = &ctx.rules;
+        assert_eq!(
+            ctx.points - parsed,
+            2 * rules.assignment_usage
+                + rules.operation_usage
+                + rules.kompas_usage
+                + rules.herhaal_usage
+        );
+
+        let e = error("herhaal 2 keer {\n    stapVooruit\n}", Dialect::Standard);
+        assert_eq!(
+            e.message(),
+            "`herhaal` is only available in the extended dialect"
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(run("a = 1 + 2 * 3", Dialect::Standard), Ok(7));
+        assert_eq!(run("a = (1 + 2) * 3", Dialect::Standard), Ok(9));
+        assert_eq!(run("a = -2 + 10 % 4", Dialect::Standard), Ok(0));
+        assert_eq!(run("a = 1 + 2 * 3", Dialect::Taal20), Ok(9));
+        assert_eq!(run("a = 10 - 4 - 3", Dialect::Taal20), Ok(3));
+
+        let e = error("gebruik a\na = (1 + 2) * 3", Dialect::Taal20);
+        assert_eq!((e.location().line, e.location().column), (2, 5));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let e = run("a = 0\na = 5 / a", Dialect::Standard).expect_err("division by zero");
+        assert_eq!(e.message(), "division by zero in 5 / 0");
+        assert_eq!(e.location().line, 3);
+
+        let e = run("a = 2147483647 + 1", Dialect::Standard).expect_err("overflow");
+        assert_eq!(e.message(), "the result of 2147483647 + 1 is too large");
+
+        assert_eq!(run("a = 0\na = 5 / a", Dialect::Taal20), Ok(0));
+        assert_eq!(run("a = 2147483647 + 1", Dialect::Taal20), Ok(i32::MIN));
+    }
+
+    #[test]
+    fn logical() {
+        let code =
+            |condition: &str| format!("gebruik kompas\nals {condition} {{\n    stapVooruit\n}}");
+        let rules = Rules::default();
+        let comparison = rules.comparison_usage;
+
+        // the right side is only evaluated, and charged, when it decides the outcome
+        assert_eq!(
+            usage(&code("1 == 2 en kompas == 1"), Dialect::Extended),
+            comparison
+        );
+        assert_eq!(
+            usage(&code("1 == 1 of kompas == 1"), Dialect::Extended),
+            comparison
+        );
+        assert_eq!(
+            usage(&code("1 == 1 en kompas == 1"), Dialect::Extended),
+            2 * comparison + rules.kompas_usage
+        );
+        assert_eq!(
+            usage(&code("niet (1 == 2 of kompas == 2)"), Dialect::Extended),
+            2 * comparison + rules.kompas_usage
+        );
+
+        let e = error(&code("1 == 1 en 2 == 2"), Dialect::Standard);
+        assert_eq!((e.location().line, e.location().column), (2, 12));
+        assert_eq!(
+            e.message(),
+            "`en` is only available in the extended dialect"
+        );
+        let e = error(&code("niet 1 == 2"), Dialect::Taal20);
+        assert_eq!(
+            e.message(),
+            "`niet` is only available in the extended dialect"
+        );
+    }
+
+    #[test]
+    fn inclusive_comparers() {
+        let code = "a = 0\nals 3 >= 3 {\n    a = a + 1\n}\nals 2 >= 3 {\n    a = a + 10\n}\nals 3 <= 3 {\n    a = a + 100\n}\nals 4 <= 3 {\n    a = a + 1000\n}";
+        assert_eq!(run(code, Dialect::Standard), Ok(101));
+
+        let e = error("gebruik a\nals a <= 3 {\n    a = 1\n}", Dialect::Taal20);
+        assert_eq!((e.location().line, e.location().column), (2, 7));
+        assert_eq!(
+            e.message(),
+            "`<=` is not available in taal 20 mode, taal 20 only knows ==, !=, > and <"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let code = "// naar het doel\nstapVooruit // eerste stap\nstapVooruit";
+        let plain = "\nstapVooruit\nstapVooruit";
+        let ctx = parse(code, Dialect::Taal20);
+        assert_eq!(ctx.points, parse(plain, Dialect::Taal20).points);
+        assert_eq!(usage(code, Dialect::Taal20), usage(plain, Dialect::Taal20));
+        assert_eq!(
+            ctx.comments,
+            [
+                Comment {
+                    text: String::from(" naar het doel"),
+                    line: 1,
+                    column: 1,
+                },
+                Comment {
+                    text: String::from(" eerste stap"),
+                    line: 2,
+                    column: 13,
+                },
+            ]
+        );
+        assert_eq!(kinds(&ctx.code), ["stapVooruit", "stapVooruit"]);
+        assert_eq!(ctx.code.objects[1].position(), (3, 1));
+    }
+
+    #[test]
+    fn breek() {
+        let code =
+            "a = 0\nzolang a < 10 {\n    a = a + 1\n    als a == 3 {\n        breek\n    }\n}";
+        assert_eq!(run(code, Dialect::Standard), Ok(3));
+
+        let code = "a = 0\nherhaal 10 keer {\n    herhaal 10 keer {\n        a = a + 1\n        als a % 2 == 0 {\n            breek\n        }\n    }\n}";
+        assert_eq!(run(code, Dialect::Extended), Ok(20));
+
+        let e = error("als 1 == 1 {\n    breek\n}", Dialect::Standard);
+        assert_eq!((e.location().line, e.location().column), (2, 5));
+        assert_eq!(
+            e.message(),
+            "`breek` can only be used inside a zolang or herhaal loop"
+        );
+        let e = error("zolang 1 == 1 {\n    breek\n}", Dialect::Taal20);
+        assert_eq!(e.message(), "`breek` is not available in taal 20 mode");
+    }
+
+    #[test]
+    fn stop() {
+        let code = "stapVooruit\nals 1 == 1 {\n    stop\n}\nstapVooruit\nstapVooruit";
+        let mut ctx = parse(code, Dialect::Standard);
+        let points = ctx.points;
+        assert_eq!(
+            ctx.execute(),
+            Ok(RunOutcome::Stopped {
+                line: 3,
+                targets_reached: 0,
+                points: points + ctx.rules.comparison_usage,
+                budget: ctx.rules.budget,
+            })
+        );
+        assert_eq!(ctx.glade.griever.x, 1);
+
+        let e = error(code, Dialect::Taal20);
+        assert_eq!((e.location().line, e.location().column), (3, 5));
+        assert_eq!(e.message(), "`stop` is not available in taal 20 mode");
+    }
+}
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, fmt};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CostKind {
+    /// `gebruik` declarations.
+    Hardware,
+    /// The statements in the code, charged once while parsing.
+    Software,
+    /// Charged every time something is executed.
+    Usage,
+    /// Pushing against an obstacle.
+    Collision,
+    /// Money picked up from the glade, these are negative costs.
+    Bonus,
+}
+
+/// The points charged to a single line for one kind of statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hotspot {
+    pub line: usize,
+    pub statement: &'static str,
+    pub points: i32,
+    pub charges: u32,
+}
+
+/// Keeps track of where the points of a run are spent.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    lines: HashMap<(usize, &'static str), Hotspot>,
+    totals: HashMap<CostKind, i32>,
+}
+
+impl Profile {
+    pub fn record(&mut self, line: usize, statement: &'static str, kind: CostKind, points: i32) {
+        let hotspot = self.lines.entry((line, statement)).or_insert(Hotspot {
+            line,
+            statement,
+            points: 0,
+            charges: 0,
+        });
+        hotspot.points += points;
+        hotspot.charges += 1;
+        *self.totals.entry(kind).or_insert(0) += points;
+    }
+
+    pub fn total(&self, kind: CostKind) -> i32 {
+        self.totals.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// All lines that were charged, the most expensive first.
+    pub fn hotspots(&self) -> Vec<&Hotspot> {
+        let mut hotspots: Vec<&Hotspot> = self.lines.values().collect();
+        hotspots.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(a.line.cmp(&b.line))
+                .then(a.statement.cmp(b.statement))
+        });
+        hotspots
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>6}  {:<14}{:>8}{:>9}",
+            "line", "statement", "points", "charges"
+        )?;
+        for hotspot in self.hotspots() {
+            writeln!(
+                f,
+                "{:>6}  {:<14}{:>8}{:>9}",
+                hotspot.line, hotspot.statement, hotspot.points, hotspot.charges
+            )?;
+        }
+        write!(
+            f,
+            "\nhardware: {}, software: {}, usage: {}, collisions: {}, bonuses: {}",
+            self.total(CostKind::Hardware),
+            self.total(CostKind::Software),
+            self.total(CostKind::Usage),
+            self.total(CostKind::Collision),
+            self.total(CostKind::Bonus)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::Glade, objects::Context};
+
+    #[test]
+    fn charges() {
+        let code = "gebruik a\nstapVooruit\na = 1\nstapVooruit";
+        let glade = Glade::parse_str("glade.csv", "s1;m3;q;t1").expect("the test glade is valid");
+        let mut ctx = Context::new(code, glade);
+        ctx.parse().expect("the code is valid");
+        ctx.execute().expect("the code runs");
+
+        let profile = &ctx.profile;
+        assert_eq!(profile.total(CostKind::Hardware), 1);
+        assert_eq!(profile.total(CostKind::Software), 6);
+        assert_eq!(profile.total(CostKind::Usage), 1);
+        assert_eq!(profile.total(CostKind::Collision), 650);
+        assert_eq!(profile.total(CostKind::Bonus), -8);
+        assert_eq!(
+            profile
+                .hotspots()
+                .iter()
+                .map(|h| (h.line, h.statement, h.points, h.charges))
+                .collect::<Vec<_>>(),
+            [
+                (4, "stapVooruit", 652, 3),
+                (3, "assignment", 3, 2),
+                (1, "gebruik", 1, 1),
+                (2, "stapVooruit", -6, 3),
+            ]
+        );
+    }
+}
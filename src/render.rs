@@ -0,0 +1,124 @@
+use super::{
+    map::{Content, Direction, Glade},
+    rules::Colors,
+};
+
+/// Resets the colours at the end of every line.
+const RESET: &str = "\x1b[0m";
+
+/// The 256 colour ansi background and foreground of a colour value. The value
+/// is looked up in `colors`, so a rules file with other values still draws
+/// every tile in the colour it stands for.
+fn ansi(colors: &Colors, value: i32) -> (u8, u8) {
+    const WHITE: u8 = 15;
+    const BLACK: u8 = 16;
+
+    let palette = [
+        (colors.white, WHITE, BLACK),
+        (colors.gray, 244, BLACK),
+        (colors.red, 160, WHITE),
+        (colors.orange, 208, BLACK),
+        (colors.yellow, 226, BLACK),
+        (colors.green, 34, WHITE),
+        (colors.blue, 27, WHITE),
+        (colors.purple, 91, WHITE),
+        (colors.black, BLACK, WHITE),
+    ];
+    IntoIterator::into_iter(palette)
+        .find(|(v, ..)| *v == value)
+        .map_or((BLACK, WHITE), |(_, background, foreground)| {
+            (background, foreground)
+        })
+}
+
+fn arrow(direction: &Direction) -> char {
+    match direction {
+        Direction::North => '↑',
+        Direction::East => '→',
+        Direction::South => '↓',
+        Direction::West => '←',
+    }
+}
+
+impl Glade {
+    /// Draws the glade with ansi colours for a terminal, with the griever as
+    /// an arrow in the direction it faces. Rows and columns are numbered from
+    /// 1, like the positions in warnings and outcomes.
+    pub fn render(&self, colors: &Colors) -> String {
+        let header: Vec<String> = (1..=self.width()).map(|x| format!("{x:^3}")).collect();
+        let mut lines = vec![format!("    {}", header.concat())];
+
+        for (y, row) in self.map.rows().enumerate() {
+            let tiles: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(x, content)| {
+                    let (background, foreground) = ansi(colors, content.get_color_value(colors));
+                    let glyph = if (x, y) == (self.griever.x, self.griever.y) {
+                        arrow(&self.griever.direction).to_string()
+                    } else {
+                        self.glyph(content)
+                    };
+                    format!("\x1b[48;5;{background}m\x1b[38;5;{foreground}m{glyph:^3}")
+                })
+                .collect();
+            lines.push(format!("{:>3} {}{RESET}", y + 1, tiles.concat()));
+        }
+        lines.join("\n")
+    }
+
+    /// The text on a tile, bombs show the steps left until they explode.
+    fn glyph(&self, content: &Content) -> String {
+        match content {
+            Content::Obstacle => String::from("▒▒▒"),
+            Content::Bomb(seconds, None) => format!("*{seconds}"),
+            Content::Bomb(_, Some(at)) => format!("*{}", (at - self.seconds()).max(0)),
+            Content::Target(t) => format!("T{}", t + 1),
+            Content::Money(amount) => format!("${amount}"),
+            Content::Turner(0) => String::from("↻?"),
+            Content::Turner(times) => format!("↻{times}"),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let mut glade = Glade::parse_str("glade.csv", "s1;x4;w;t1\nw;q;m2;d0")
+            .expect("the test glade is valid");
+        glade.forward().expect("the step is possible");
+        glade.forward().expect("the step is possible");
+        let tile = |background: u8, foreground: u8, glyph: &str| {
+            format!("\x1b[48;5;{background}m\x1b[38;5;{foreground}m{glyph}")
+        };
+
+        // the bomb was armed with 4 steps to go by the first step
+        let rendered = glade.render(&Colors::default());
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "     1  2  3  4 ");
+        assert_eq!(
+            lines[1],
+            format!(
+                "  1 {}{}{}{}{RESET}",
+                tile(16, 15, "   "),
+                tile(16, 15, "*3 "),
+                tile(15, 16, " → "),
+                tile(226, 16, "T1 ")
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "  2 {}{}{}{}{RESET}",
+                tile(15, 16, "   "),
+                tile(16, 15, "▒▒▒"),
+                tile(226, 16, "$2 "),
+                tile(27, 15, "↻? ")
+            )
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use super::{
+    error::{InterpretError, InterpretResult, Location},
+    weights::*,
+};
+
+/// The value `kleurOog` reports for every colour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Colors {
+    pub white: i32,
+    pub gray: i32,
+    pub red: i32,
+    pub orange: i32,
+    pub yellow: i32,
+    pub green: i32,
+    pub blue: i32,
+    pub purple: i32,
+    pub black: i32,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self {
+            white: WHITE_SQUARE,
+            gray: GRAY_SQUARE,
+            red: RED_SQUARE,
+            orange: ORANGE_SQUARE,
+            yellow: YELLOW_SQUARE,
+            green: GREEN_SQUARE,
+            blue: BLUE_SQUARE,
+            purple: PURPLE_SQUARE,
+            black: BLACK_SQUARE,
+        }
+    }
+}
+
+/// The budget, costs and colour values of a challenge. The defaults are the
+/// rules of 2020, from `weights.rs`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+    pub budget: i32,
+    pub kompas_hardware: i32,
+    pub zwoog_hardware: i32,
+    pub kleuroog_hardware: i32,
+    pub var_hardware: i32,
+    pub stepforward_usage: i32,
+    pub stepbackwards_usage: i32,
+    pub turnleft_usage: i32,
+    pub turnright_usage: i32,
+    pub zwoog_usage: i32,
+    pub kleuroog_usage: i32,
+    pub kompas_usage: i32,
+    pub push_obstacle: i32,
+    pub assignment_usage: i32,
+    pub operation_usage: i32,
+    pub comparison_usage: i32,
+    pub zolang_software: i32,
+    pub herhaal_software: i32,
+    pub herhaal_usage: i32,
+    pub als_software: i32,
+    pub action_software: i32,
+    pub assignment_software: i32,
+    pub functie_software: i32,
+    pub roep_software: i32,
+    pub breek_software: i32,
+    pub stop_software: i32,
+    pub colors: Colors,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            budget: 2020,
+            kompas_hardware: KOMPAS_HARDWARE,
+            zwoog_hardware: ZWOOG_HARDWARE,
+            kleuroog_hardware: KLEUROOG_HARDWARE,
+            var_hardware: VAR_HARDWARE,
+            // steps have always been free here, even though `weights.rs` lists a cost
+            stepforward_usage: 0,
+            stepbackwards_usage: 0,
+            turnleft_usage: TURNLEFT_USAGE,
+            turnright_usage: TURNRIGHT_USAGE,
+            zwoog_usage: ZWOOG_USAGE,
+            kleuroog_usage: KLEUROOG_USAGE,
+            kompas_usage: KOMPAS_USAGE,
+            push_obstacle: PUSH_OBSTACLE,
+            assignment_usage: ASSIGNMENT_USAGE,
+            operation_usage: OPERATION_USAGE,
+            comparison_usage: COMPARISON_USAGE,
+            zolang_software: ZOLANG_SOFTWARE,
+            herhaal_software: HERHAAL_SOFTWARE,
+            herhaal_usage: HERHAAL_USAGE,
+            als_software: ALS_SOFTWARE,
+            action_software: ACTION_SOFTWARE,
+            assignment_software: ASSIGNMENT_SOFTWARE,
+            functie_software: FUNCTIE_SOFTWARE,
+            roep_software: ROEP_SOFTWARE,
+            breek_software: BREEK_SOFTWARE,
+            stop_software: STOP_SOFTWARE,
+            colors: Colors::default(),
+        }
+    }
+}
+
+impl Rules {
+    pub fn parse(path: &str) -> InterpretResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            InterpretError::rules(
+                Location::new(path, 0, 0),
+                format!("unable to read rules file: {e}"),
+            )
+        })?;
+        Self::parse_str(path, &text)
+    }
+
+    /// Reads `key = value` lines on top of the default rules, keys are the
+    /// names of the constants in `weights.rs` (or `budget`) in any case.
+    /// Comments starting with `#`, `[section]` headers and keys with a text
+    /// value are skipped, so simple TOML files can be used as well.
+    pub fn parse_str(path: &str, text: &str) -> InterpretResult<Self> {
+        let mut rules = Self::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() || line.starts_with('[') {
+                continue;
+            }
+
+            let location = Location::new(path, i + 1, 1);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(InterpretError::rules(
+                    location,
+                    format!("expected `key = value`, found `{line}`"),
+                ));
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            let Some(field) = rules.field(key) else {
+                // no rule is text, so strings like the name of the challenge are skipped
+                if value.starts_with('"') || value.starts_with('\'') {
+                    continue;
+                }
+                return Err(InterpretError::rules(
+                    location,
+                    format!("unknown rule {key}"),
+                ));
+            };
+            *field = value.parse().map_err(|_| {
+                InterpretError::rules(
+                    location,
+                    format!("the value of {key} has to be a number, found `{value}`"),
+                )
+            })?;
+        }
+
+        Ok(rules)
+    }
+
+    fn field(&mut self, key: &str) -> Option<&mut i32> {
+        Some(match key.to_ascii_lowercase().as_str() {
+            "budget" => &mut self.budget,
+            "kompas_hardware" => &mut self.kompas_hardware,
+            "zwoog_hardware" => &mut self.zwoog_hardware,
+            "kleuroog_hardware" => &mut self.kleuroog_hardware,
+            "var_hardware" => &mut self.var_hardware,
+            "stepforward_usage" => &mut self.stepforward_usage,
+            "stepbackwards_usage" => &mut self.stepbackwards_usage,
+            "turnleft_usage" => &mut self.turnleft_usage,
+            "turnright_usage" => &mut self.turnright_usage,
+            "zwoog_usage" => &mut self.zwoog_usage,
+            "kleuroog_usage" => &mut self.kleuroog_usage,
+            "kompas_usage" => &mut self.kompas_usage,
+            "push_obstacle" => &mut self.push_obstacle,
+            "assignment_usage" => &mut self.assignment_usage,
+            "operation_usage" => &mut self.operation_usage,
+            "comparison_usage" => &mut self.comparison_usage,
+            "zolang_software" => &mut self.zolang_software,
+            "herhaal_software" => &mut self.herhaal_software,
+            "herhaal_usage" => &mut self.herhaal_usage,
+            "als_software" => &mut self.als_software,
+            "action_software" => &mut self.action_software,
+            "assignment_software" => &mut self.assignment_software,
+            "functie_software" => &mut self.functie_software,
+            "roep_software" => &mut self.roep_software,
+            "breek_software" => &mut self.breek_software,
+            "stop_software" => &mut self.stop_software,
+            "white_square" => &mut self.colors.white,
+            "gray_square" => &mut self.colors.gray,
+            "red_square" => &mut self.colors.red,
+            "orange_square" => &mut self.colors.orange,
+            "yellow_square" => &mut self.colors.yellow,
+            "green_square" => &mut self.colors.green,
+            "blue_square" => &mut self.colors.blue,
+            "purple_square" => &mut self.colors.purple,
+            "black_square" => &mut self.colors.black,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml() {
+        let text = "name = \"a-mazing 2021\" # the year\nbudget = 2021\n\n[costs]\nKLEUROOG_HARDWARE = 20\nstepforward_usage = 2\n\n[colors]\nyellow_square = 9\n";
+        let rules = Rules::parse_str("rules.toml", text).expect("the rules are valid");
+        assert_eq!(rules.budget, 2021);
+        assert_eq!(rules.kleuroog_hardware, 20);
+        assert_eq!(rules.colors.yellow, 9);
+        assert_eq!(rules.stepforward_usage, 2);
+        assert_eq!(rules.stepbackwards_usage, 0);
+        assert_eq!(rules.kompas_hardware, KOMPAS_HARDWARE);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text| {
+            Rules::parse_str("rules.toml", text)
+                .expect_err("the rules are invalid")
+                .to_string()
+        };
+        assert_eq!(
+            error("budget = \"2021\""),
+            "rules.toml:1:1: rules error: the value of budget has to be a number, found `\"2021\"`"
+        );
+        assert_eq!(
+            error("\nbudgte = 2021"),
+            "rules.toml:2:1: rules error: unknown rule budgte"
+        );
+        assert_eq!(
+            error("budget 2021"),
+            "rules.toml:1:1: rules error: expected `key = value`, found `budget 2021`"
+        );
+    }
+}
@@ -14,9 +14,15 @@ pub const ASSIGNMENT_USAGE: i32 = 1;
 pub const OPERATION_USAGE: i32 = 2;
 pub const COMPARISON_USAGE: i32 = 1;
 pub const ZOLANG_SOFTWARE: i32 = 4;
+pub const HERHAAL_SOFTWARE: i32 = 4;
+pub const HERHAAL_USAGE: i32 = 1;
 pub const ALS_SOFTWARE: i32 = 4;
 pub const ACTION_SOFTWARE: i32 = 2;
 pub const ASSIGNMENT_SOFTWARE: i32 = 2;
+pub const FUNCTIE_SOFTWARE: i32 = 4;
+pub const ROEP_SOFTWARE: i32 = 2;
+pub const BREEK_SOFTWARE: i32 = 2;
+pub const STOP_SOFTWARE: i32 = 2;
 
 pub const WHITE_SQUARE: i32 = 8;
 pub const GRAY_SQUARE: i32 = 7;